@@ -2,8 +2,11 @@
 
 pub mod prelude;
 
+pub mod post_revisions;
+pub mod post_slug_history;
 pub mod post_tags;
 pub mod posts;
 pub mod sea_orm_active_enums;
+pub mod tag_aliases;
 pub mod tags;
 pub mod users;
@@ -22,7 +22,7 @@ pub enum Relation {
         from = "Column::PostId",
         to = "super::posts::Column::Id",
         on_update = "NoAction",
-        on_delete = "NoAction"
+        on_delete = "Cascade"
     )]
     Posts,
     #[sea_orm(
@@ -23,6 +23,10 @@ pub struct Model {
     pub subheading: String,
     pub last_edit: Option<DateTime>,
     pub post_status: PostsStatusEnum,
+    pub publish_at: Option<DateTime>,
+    pub unpublish_at: Option<DateTime>,
+    pub deleted_at: Option<DateTime>,
+    pub language: String,
     #[sea_orm(column_type = "custom(\"tsvector\")")]
     #[oai(skip)]
     #[serde(skip)]
@@ -33,10 +37,21 @@ pub struct Model {
     #[serde(skip)]
     #[sea_orm(ignore)]
     pub author_search: String,
+    #[sea_orm(column_type = "custom(\"tsvector\")")]
+    #[oai(skip)]
+    #[serde(skip)]
+    #[sea_orm(ignore)]
+    pub search_vector: String,
+    pub translation_group_id: Uuid,
+    pub published_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::post_revisions::Entity")]
+    PostRevisions,
+    #[sea_orm(has_many = "super::post_slug_history::Entity")]
+    PostSlugHistory,
     #[sea_orm(has_many = "super::post_tags::Entity")]
     PostTags,
     #[sea_orm(
@@ -49,6 +64,18 @@ pub enum Relation {
     Users,
 }
 
+impl Related<super::post_revisions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostRevisions.def()
+    }
+}
+
+impl Related<super::post_slug_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PostSlugHistory.def()
+    }
+}
+
 impl Related<super::post_tags::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::PostTags.def()
@@ -74,6 +101,10 @@ impl ActiveModelBehavior for ActiveModel {}
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelatedEntity)]
 pub enum RelatedEntity {
+    #[sea_orm(entity = "super::post_revisions::Entity")]
+    PostRevisions,
+    #[sea_orm(entity = "super::post_slug_history::Entity")]
+    PostSlugHistory,
     #[sea_orm(entity = "super::post_tags::Entity")]
     PostTags,
     #[sea_orm(entity = "super::users::Entity")]
@@ -13,12 +13,25 @@ pub struct Model {
     pub id: Uuid,
     #[sea_orm(unique)]
     pub name: String,
+    #[sea_orm(unique)]
+    pub normalized_name: String,
+    pub parent_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::post_tags::Entity")]
     PostTags,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentId",
+        to = "Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    SelfRef,
+    #[sea_orm(has_many = "super::tag_aliases::Entity")]
+    TagAliases,
 }
 
 impl Related<super::post_tags::Entity> for Entity {
@@ -27,6 +40,12 @@ impl Related<super::post_tags::Entity> for Entity {
     }
 }
 
+impl Related<super::tag_aliases::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TagAliases.def()
+    }
+}
+
 impl Related<super::posts::Entity> for Entity {
     fn to() -> RelationDef {
         super::post_tags::Relation::Posts.def()
@@ -42,6 +61,8 @@ impl ActiveModelBehavior for ActiveModel {}
 pub enum RelatedEntity {
     #[sea_orm(entity = "super::post_tags::Entity")]
     PostTags,
+    #[sea_orm(entity = "super::tag_aliases::Entity")]
+    TagAliases,
     #[sea_orm(entity = "super::posts::Entity")]
     Posts,
 }
\ No newline at end of file
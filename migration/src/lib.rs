@@ -1,12 +1,40 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20261017_000001_create_post_revisions;
+mod m20261017_000002_add_post_schedule;
+mod m20261017_000003_create_post_slug_history;
+mod m20261017_000004_add_tag_normalized_names;
+mod m20261017_000005_add_tag_parents;
+mod m20261017_000006_cascade_post_tags;
+mod m20261017_000007_add_post_deleted_at;
+mod m20261017_000008_add_post_search_vector;
+mod m20261017_000009_add_trigram_indexes;
+mod m20261017_000010_add_title_prefix_index;
+mod m20261017_000011_add_post_language;
+mod m20261017_000012_add_post_translations;
+mod m20261017_000013_add_post_published_at;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20261017_000001_create_post_revisions::Migration),
+            Box::new(m20261017_000002_add_post_schedule::Migration),
+            Box::new(m20261017_000003_create_post_slug_history::Migration),
+            Box::new(m20261017_000004_add_tag_normalized_names::Migration),
+            Box::new(m20261017_000005_add_tag_parents::Migration),
+            Box::new(m20261017_000006_cascade_post_tags::Migration),
+            Box::new(m20261017_000007_add_post_deleted_at::Migration),
+            Box::new(m20261017_000008_add_post_search_vector::Migration),
+            Box::new(m20261017_000009_add_trigram_indexes::Migration),
+            Box::new(m20261017_000010_add_title_prefix_index::Migration),
+            Box::new(m20261017_000011_add_post_language::Migration),
+            Box::new(m20261017_000012_add_post_translations::Migration),
+            Box::new(m20261017_000013_add_post_published_at::Migration),
+        ]
     }
 }
@@ -0,0 +1,104 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostRevisions::Table)
+                    .if_not_exists()
+                    .col(uuid(PostRevisions::Id).primary_key().not_null())
+                    .col(uuid(PostRevisions::PostId).not_null())
+                    .col(integer(PostRevisions::RevisionNumber).not_null())
+                    .col(string(PostRevisions::Title).not_null())
+                    .col(string(PostRevisions::Subheading).not_null())
+                    .col(string(PostRevisions::Body).not_null())
+                    .col(json_binary(PostRevisions::Tags).not_null())
+                    .col(
+                        ColumnDef::new(PostRevisions::PostStatus)
+                            .custom(PostsStatusEnum)
+                            .not_null(),
+                    )
+                    .col(uuid_null(PostRevisions::EditedBy))
+                    .col(date_time(PostRevisions::CreatedAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_post_revisions_post_id")
+                            .from(PostRevisions::Table, PostRevisions::PostId)
+                            .to(Posts::Table, Posts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_post_revisions_post_id_revision_number")
+                    .table(PostRevisions::Table)
+                    .col(PostRevisions::PostId)
+                    .col(PostRevisions::RevisionNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing posts start their history with a snapshot of their current state
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                INSERT INTO post_revisions
+                    (id, post_id, revision_number, title, subheading, body, tags, post_status, edited_by, created_at)
+                SELECT
+                    gen_random_uuid(), p.id, 1, p.title, p.subheading, p.body,
+                    COALESCE(
+                        (SELECT jsonb_agg(t.name ORDER BY t.name)
+                         FROM post_tags pt JOIN tags t ON t.id = pt.tag_id
+                         WHERE pt.post_id = p.id),
+                        '[]'::jsonb
+                    ),
+                    p.post_status, p.created_by, COALESCE(p.last_edit, p.creation_time)
+                FROM posts p
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostRevisions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+struct PostsStatusEnum;
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum PostRevisions {
+    Table,
+    Id,
+    PostId,
+    RevisionNumber,
+    Title,
+    Subheading,
+    Body,
+    Tags,
+    PostStatus,
+    EditedBy,
+    CreatedAt,
+}
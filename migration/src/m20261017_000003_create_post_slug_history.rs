@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostSlugHistory::Table)
+                    .if_not_exists()
+                    .col(string(PostSlugHistory::Slug).primary_key().not_null())
+                    .col(uuid(PostSlugHistory::PostId).not_null())
+                    .col(date_time(PostSlugHistory::RetiredAt).default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_post_slug_history_post_id")
+                            .from(PostSlugHistory::Table, PostSlugHistory::PostId)
+                            .to(Posts::Table, Posts::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_post_slug_history_post_id")
+                    .table(PostSlugHistory::Table)
+                    .col(PostSlugHistory::PostId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostSlugHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum PostSlugHistory {
+    Table,
+    Slug,
+    PostId,
+    RetiredAt,
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use sea_orm_migration::{prelude::*, schema::*, sea_orm::Statement};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tags::Table)
+                    .add_column(string_null(Tags::NormalizedName))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TagAliases::Table)
+                    .if_not_exists()
+                    .col(string(TagAliases::NormalizedName).primary_key().not_null())
+                    .col(uuid(TagAliases::TagId).not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_tag_aliases_tag_id")
+                            .from(TagAliases::Table, TagAliases::TagId)
+                            .to(Tags::Table, Tags::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tag_aliases_tag_id")
+                    .table(TagAliases::Table)
+                    .col(TagAliases::TagId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Tags whose names only differ in case or spacing are merged into the
+        // alphabetically first of them. Every merge is listed before any is made.
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+        let rows = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT id::text AS id, name FROM tags ORDER BY name",
+            ))
+            .await?;
+        let mut tags_by_key: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+        for row in rows {
+            let id: String = row.try_get("", "id")?;
+            let name: String = row.try_get("", "name")?;
+            let mut key = normalized_name(&name);
+            if key.is_empty() {
+                key = format!("tag_{}", id.replace('-', ""));
+            }
+            tags_by_key.entry(key).or_default().push((id, name));
+        }
+
+        for tags in tags_by_key.values().filter(|tags| tags.len() > 1) {
+            let names: Vec<&str> = tags.iter().map(|(_, name)| name.as_str()).collect();
+            println!("Merging tags {:?} into {:?}", &names[1..], names[0]);
+        }
+
+        for (key, tags) in tags_by_key {
+            let ((canonical, _), duplicates) = tags.split_first().expect("groups are never empty");
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                "UPDATE tags SET normalized_name = $1 WHERE id = $2::uuid",
+                [key.into(), canonical.clone().into()],
+            ))
+            .await?;
+            for (duplicate, _) in duplicates {
+                db.execute(Statement::from_sql_and_values(
+                    backend,
+                    r#"
+                    INSERT INTO post_tags (post_id, tag_id)
+                    SELECT post_id, $1::uuid FROM post_tags WHERE tag_id = $2::uuid
+                    ON CONFLICT DO NOTHING
+                    "#,
+                    [canonical.clone().into(), duplicate.clone().into()],
+                ))
+                .await?;
+                db.execute(Statement::from_sql_and_values(
+                    backend,
+                    "DELETE FROM post_tags WHERE tag_id = $1::uuid",
+                    [duplicate.clone().into()],
+                ))
+                .await?;
+                db.execute(Statement::from_sql_and_values(
+                    backend,
+                    "DELETE FROM tags WHERE id = $1::uuid",
+                    [duplicate.clone().into()],
+                ))
+                .await?;
+            }
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tags::Table)
+                    .modify_column(string(Tags::NormalizedName).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tags_normalized_name")
+                    .table(Tags::Table)
+                    .col(Tags::NormalizedName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TagAliases::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tags_normalized_name")
+                    .table(Tags::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Tags::Table)
+                    .drop_column(Tags::NormalizedName)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Same normalization as `normalized_name` in the service at the time of this migration.
+/// Punctuation is kept, "C", "C++" and "C#" are different tags.
+fn normalized_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[derive(DeriveIden)]
+enum Tags {
+    Table,
+    Id,
+    NormalizedName,
+}
+
+#[derive(DeriveIden)]
+enum TagAliases {
+    Table,
+    NormalizedName,
+    TagId,
+}
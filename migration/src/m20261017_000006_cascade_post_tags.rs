@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        replace_post_foreign_key(manager, ForeignKeyAction::Cascade).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        replace_post_foreign_key(manager, ForeignKeyAction::NoAction).await
+    }
+}
+
+/// Foreign key actions can't be altered in place, the key is dropped and created again.
+/// `fk_post_tags_tag_id` stays as it is, tags delete their rows explicitly.
+async fn replace_post_foreign_key(
+    manager: &SchemaManager<'_>,
+    on_delete: ForeignKeyAction,
+) -> Result<(), DbErr> {
+    manager
+        .drop_foreign_key(
+            ForeignKey::drop()
+                .name("fk_post_tags_post_id")
+                .table(PostTags::Table)
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .create_foreign_key(
+            ForeignKey::create()
+                .name("fk_post_tags_post_id")
+                .from(PostTags::Table, PostTags::PostId)
+                .to(Posts::Table, Posts::Id)
+                .on_delete(on_delete)
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum PostTags {
+    Table,
+    PostId,
+}
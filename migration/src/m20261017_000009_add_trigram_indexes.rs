@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+        db.execute_unprepared(
+            "CREATE INDEX idx_posts_title_trgm ON posts USING GIN (title gin_trgm_ops)",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE INDEX idx_posts_author_trgm ON posts USING GIN (author gin_trgm_ops)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The extension is left installed, other schemas may depend on it
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_posts_author_trgm")
+            .await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS idx_posts_title_trgm")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Text search configuration of each language posts can be written in, anything else
+/// is indexed without stemming. Must stay in sync with `Language` in the service.
+const SEARCH_CONFIG_FUNCTION: &str = r#"
+    CREATE OR REPLACE FUNCTION post_search_config(language TEXT) RETURNS regconfig
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE AS $$
+        SELECT CASE language
+            WHEN 'en' THEN 'english'::regconfig
+            WHEN 'pt' THEN 'portuguese'::regconfig
+            WHEN 'es' THEN 'spanish'::regconfig
+            WHEN 'fr' THEN 'french'::regconfig
+            WHEN 'de' THEN 'german'::regconfig
+            WHEN 'it' THEN 'italian'::regconfig
+            ELSE 'simple'::regconfig
+        END
+    $$
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column(string(Posts::Language).default("en"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(SEARCH_CONFIG_FUNCTION)
+            .await?;
+
+        // Generation expressions can't be altered, so the columns are recreated
+        drop_search_columns(manager).await?;
+        add_search_columns(manager, "post_search_config(language)").await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        drop_search_columns(manager).await?;
+        add_search_columns(manager, "'english'").await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION IF EXISTS post_search_config(TEXT)")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::Language)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Dropping `search_vector` drops its GIN index along with it
+async fn drop_search_columns(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Posts::Table)
+                .drop_column(Posts::TitleSearch)
+                .drop_column(Posts::AuthorSearch)
+                .drop_column(Posts::SearchVector)
+                .to_owned(),
+        )
+        .await
+}
+
+/// Recreates the generated search columns, stemming with the `config` SQL expression
+async fn add_search_columns(manager: &SchemaManager<'_>, config: &str) -> Result<(), DbErr> {
+    manager
+        .alter_table(
+            Table::alter()
+                .table(Posts::Table)
+                .add_column(custom(Posts::TitleSearch, "TSVECTOR").extra(format!(
+                    "GENERATED ALWAYS AS (to_tsvector({config}, title)) STORED"
+                )))
+                .add_column(custom(Posts::AuthorSearch, "TSVECTOR").extra(format!(
+                    "GENERATED ALWAYS AS (to_tsvector({config}, author)) STORED"
+                )))
+                .add_column(custom(Posts::SearchVector, "TSVECTOR").extra(format!(
+                    "GENERATED ALWAYS AS (
+                        setweight(to_tsvector({config}, title), 'A')
+                        || setweight(to_tsvector({config}, subheading), 'B')
+                        || setweight(to_tsvector({config}, body), 'C')
+                    ) STORED"
+                )))
+                .to_owned(),
+        )
+        .await?;
+
+    manager
+        .get_connection()
+        .execute_unprepared(
+            "CREATE INDEX idx_posts_search_vector ON posts USING GIN (search_vector)",
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    Language,
+    TitleSearch,
+    AuthorSearch,
+    SearchVector,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column(uuid_null(Posts::TranslationGroupId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every existing post starts out as the only member of its own group
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE posts SET translation_group_id = id")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .modify_column(uuid(Posts::TranslationGroupId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // One post per language in a group, trashed posts don't hold on to theirs
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE UNIQUE INDEX idx_posts_translation_group_language
+                ON posts (translation_group_id, language)
+                WHERE deleted_at IS NULL
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::TranslationGroupId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    TranslationGroupId,
+}
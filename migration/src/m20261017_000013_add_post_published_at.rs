@@ -0,0 +1,56 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column(date_time_null(Posts::PublishedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        // The first revision recorded as published is the best record there is,
+        // posts published before revisions were kept fall back to their creation
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                UPDATE posts SET published_at = COALESCE(
+                    (
+                        SELECT min(post_revisions.created_at) FROM post_revisions
+                        WHERE post_revisions.post_id = posts.id
+                        AND post_revisions.post_status = 'published'
+                    ),
+                    creation_time
+                )
+                WHERE post_status IN ('published', 'archived')
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::PublishedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Posts {
+    Table,
+    PublishedAt,
+}
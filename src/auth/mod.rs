@@ -23,6 +23,7 @@ impl Permission {
     }
 
     /// Parse from "action:resource" format
+    #[allow(dead_code)]
     pub fn from_string(permission_str: &str) -> Option<Self> {
         let parts: Vec<&str> = permission_str.split(':').collect();
         if parts.len() == 2 {
@@ -31,10 +32,12 @@ impl Permission {
             None
         }
     }
+}
 
-    /// Convert to "action:resource" format
-    pub fn to_string(&self) -> String {
-        format!("{}:{}", self.action, self.resource)
+/// Formats as "action:resource"
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.action, self.resource)
     }
 }
 
@@ -65,6 +68,7 @@ impl BearerAuthorization {
     }
 
     /// Check if the user has any of the specified permissions
+    #[allow(dead_code)]
     pub fn has_any_permission(&self, permissions: &[(String, String)]) -> bool {
         permissions.iter().any(|(action, resource)| {
             self.has_permission(action, resource)
@@ -72,6 +76,24 @@ impl BearerAuthorization {
     }
 }
 
+/// Bearer authorization for endpoints that also serve anonymous callers
+#[derive(SecurityScheme)]
+pub enum OptionalBearerAuthorization {
+    Bearer(BearerAuthorization),
+    #[oai(fallback)]
+    Anonymous,
+}
+
+impl OptionalBearerAuthorization {
+    /// Claims of the caller, `None` when the request is anonymous
+    pub fn claims(&self) -> Option<&BearerAuthorization> {
+        match self {
+            Self::Bearer(claims) => Some(claims),
+            Self::Anonymous => None,
+        }
+    }
+}
+
 async fn key_checker(_: &Request, token: Bearer) -> Option<Claims> {
     let decoding_key = jsonwebtoken::DecodingKey::from_rsa_pem(CONFIG.jwt_public_key.as_bytes()).ok()?;
     let Ok(token) = decode(
@@ -4,6 +4,18 @@ use std::env;
 pub struct AppConfig {
     pub database_url: String,
     pub jwt_public_key: String,
+    /// How often scheduled publishing runs, in seconds
+    pub scheduler_interval_secs: u64,
+    /// How often tags without posts are deleted, in seconds
+    pub orphan_tags_interval_secs: u64,
+    /// How often expired posts are purged from the trash, in seconds
+    pub trash_purge_interval_secs: u64,
+    /// How many days a deleted post stays in the trash before it is purged
+    pub trash_retention_days: i64,
+    /// `Cache-Control` sent with anonymous read responses
+    pub cache_control: String,
+    /// Longest a title suggestion query may run before it is cancelled, in milliseconds
+    pub suggest_timeout_ms: u64,
 }
 
 pub static CONFIG: Lazy<AppConfig> = Lazy::new(|| AppConfig {
@@ -11,4 +23,26 @@ pub static CONFIG: Lazy<AppConfig> = Lazy::new(|| AppConfig {
     jwt_public_key: env::var("JWT_PUBLIC_KEY")
         .expect("JWT public key not set")
         .replace("\\n", "\n"),
+    scheduler_interval_secs: env::var("SCHEDULER_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(30),
+    orphan_tags_interval_secs: env::var("ORPHAN_TAGS_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(3600),
+    trash_purge_interval_secs: env::var("TRASH_PURGE_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(3600),
+    trash_retention_days: env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(30),
+    cache_control: env::var("CACHE_CONTROL")
+        .unwrap_or_else(|_| "public, max-age=60, stale-while-revalidate=300".to_string()),
+    suggest_timeout_ms: env::var("SUGGEST_TIMEOUT_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .unwrap_or(250),
 });
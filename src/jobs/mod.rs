@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use sea_orm::DatabaseConnection;
+
+use crate::config::CONFIG;
+
+pub(crate) mod orphan_tags;
+mod scheduled_publishing;
+mod trash_retention;
+
+/// Starts every background job on the tokio runtime
+pub fn spawn_all(db: &DatabaseConnection) {
+    tokio::spawn(scheduled_publishing::run(
+        db.clone(),
+        Duration::from_secs(CONFIG.scheduler_interval_secs),
+    ));
+    tokio::spawn(orphan_tags::run(
+        db.clone(),
+        Duration::from_secs(CONFIG.orphan_tags_interval_secs),
+    ));
+    tokio::spawn(trash_retention::run(
+        db.clone(),
+        Duration::from_secs(CONFIG.trash_purge_interval_secs),
+        chrono::Duration::days(CONFIG.trash_retention_days),
+    ));
+}
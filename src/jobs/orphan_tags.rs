@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use entities::tags::Entity as Tags;
+use migration::Expr;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    TransactionTrait,
+};
+use tracing::{error, info};
+use uuid::Uuid;
+
+pub async fn run(db: DatabaseConnection, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match remove_orphan_tags(&db, false).await {
+            Ok(tags) if tags.is_empty() => {}
+            Ok(tags) => info!("orphan tag cleanup removed {} tags", tags.len()),
+            Err(err) => error!("orphan tag cleanup failed: {err}"),
+        }
+    }
+}
+
+/// Tags no post carries. Tags with sub tags are kept, they give the category tree its shape.
+fn orphaned() -> migration::SimpleExpr {
+    Expr::cust(
+        r#"NOT EXISTS (SELECT 1 FROM post_tags WHERE post_tags.tag_id = "tags"."id")
+        AND NOT EXISTS (SELECT 1 FROM tags AS children WHERE children.parent_id = "tags"."id")"#,
+    )
+}
+
+/// Deletes every orphaned tag, along with its aliases, and returns them.
+/// With `dry_run` nothing is deleted and the tags that would be are returned.
+pub(crate) async fn remove_orphan_tags(
+    db: &DatabaseConnection,
+    dry_run: bool,
+) -> Result<Vec<entities::tags::Model>, DbErr> {
+    if dry_run {
+        return Tags::find()
+            .filter(orphaned())
+            .order_by_asc(entities::tags::Column::Name)
+            .all(db)
+            .await;
+    }
+
+    // Locking the candidates waits for the transactions attaching them, which hold a
+    // lock taken in `create_tag_if_not_exists`. The delete then checks again with a
+    // fresh snapshot, so tags attached in the meantime are kept.
+    let tnx = db.begin().await?;
+    let candidates: Vec<Uuid> = Tags::find()
+        .select_only()
+        .column(entities::tags::Column::Id)
+        .filter(orphaned())
+        .lock_exclusive()
+        .into_tuple()
+        .all(&tnx)
+        .await?;
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut tags = Tags::delete_many()
+        .filter(entities::tags::Column::Id.is_in(candidates))
+        .filter(orphaned())
+        .exec_with_returning(&tnx)
+        .await?;
+    tnx.commit().await?;
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tags)
+}
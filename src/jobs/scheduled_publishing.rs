@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use entities::posts::Entity as Posts;
+use entities::sea_orm_active_enums::PostsStatusEnum;
+use sea_orm::sea_query::{LockBehavior, LockType};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, ModelTrait,
+    QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+use tracing::{error, info};
+
+use crate::routes::posts::{stamp_publication, touch_changed_translations};
+use crate::routes::revisions::record_revision;
+
+pub async fn run(db: DatabaseConnection, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match apply_due_transitions(&db).await {
+            Ok(0) => {}
+            Ok(count) => info!("scheduled publishing updated {count} posts"),
+            Err(err) => error!("scheduled publishing failed: {err}"),
+        }
+    }
+}
+
+/// Publishes drafts whose `publish_at` has passed and archives published posts whose
+/// `unpublish_at` has passed. Due rows are locked with `SKIP LOCKED`, so replicas
+/// running this concurrently never transition the same post twice, and the instants
+/// are cleared once applied so a post is only transitioned once per schedule.
+async fn apply_due_transitions(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let now = chrono::Utc::now().naive_utc();
+    let tnx = db.begin().await?;
+
+    let due_posts = Posts::find()
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(entities::posts::Column::PostStatus.eq(PostsStatusEnum::Draft))
+                        .add(entities::posts::Column::PublishAt.lte(now)),
+                )
+                .add(
+                    Condition::all()
+                        .add(entities::posts::Column::PostStatus.eq(PostsStatusEnum::Published))
+                        .add(entities::posts::Column::UnpublishAt.lte(now)),
+                ),
+        )
+        .filter(entities::posts::Column::DeletedAt.is_null())
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .all(&tnx)
+        .await?;
+
+    let count = due_posts.len();
+    for post_model in due_posts {
+        let mut status = post_model.post_status.clone();
+        let mut post: entities::posts::ActiveModel = post_model.clone().into();
+
+        if status == PostsStatusEnum::Draft && post_model.publish_at.is_some_and(|at| at <= now) {
+            status = PostsStatusEnum::Published;
+            post.publish_at = Set(None);
+        }
+        if status == PostsStatusEnum::Published
+            && post_model.unpublish_at.is_some_and(|at| at <= now)
+        {
+            status = PostsStatusEnum::Archived;
+            post.unpublish_at = Set(None);
+        }
+
+        post.post_status = Set(status);
+        stamp_publication(&mut post, now);
+        post.last_edit = Set(Some(now));
+        let model = post.update(&tnx).await?;
+        touch_changed_translations(&tnx, &post_model, &model).await?;
+
+        let tag_names = model
+            .find_related(entities::tags::Entity)
+            .all(&tnx)
+            .await?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        record_revision(&tnx, &model, tag_names, None).await?;
+    }
+
+    tnx.commit().await?;
+    Ok(count)
+}
@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use entities::posts::Entity as Posts;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use tracing::{error, info};
+
+pub async fn run(db: DatabaseConnection, interval: Duration, retention: chrono::Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match purge_expired(&db, retention).await {
+            Ok(0) => {}
+            Ok(count) => info!("trash retention purged {count} posts"),
+            Err(err) => error!("trash retention failed: {err}"),
+        }
+    }
+}
+
+/// Permanently deletes the posts that have been in the trash for longer than `retention`
+async fn purge_expired(db: &DatabaseConnection, retention: chrono::Duration) -> Result<u64, DbErr> {
+    let cutoff = chrono::Utc::now().naive_utc() - retention;
+    let result = Posts::delete_many()
+        .filter(entities::posts::Column::DeletedAt.lt(cutoff))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
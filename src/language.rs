@@ -0,0 +1,113 @@
+/// Languages posts can be written in. Each one is stemmed with its own text search
+/// configuration by the `post_search_config` SQL function.
+#[derive(poem_openapi::Enum, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[oai(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Pt,
+    Es,
+    Fr,
+    De,
+    It,
+}
+
+impl Language {
+    pub const ALL: [Self; 6] = [Self::En, Self::Pt, Self::Es, Self::Fr, Self::De, Self::It];
+
+    /// ISO 639-1 code, as stored in `posts.language`
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Pt => "pt",
+            Self::Es => "es",
+            Self::Fr => "fr",
+            Self::De => "de",
+            Self::It => "it",
+        }
+    }
+
+    /// Inverse of `code`, for the languages we have
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "en" => Some(Self::En),
+            "pt" => Some(Self::Pt),
+            "es" => Some(Self::Es),
+            "fr" => Some(Self::Fr),
+            "de" => Some(Self::De),
+            "it" => Some(Self::It),
+            _ => None,
+        }
+    }
+}
+
+/// Languages of an `Accept-Language` header (RFC 9110, section 12.5.4) we can serve,
+/// most preferred first. Regional variants count as their language, e.g. `pt-BR` as `pt`.
+pub fn preferred(accept_language: &str) -> Vec<Language> {
+    let mut ranges: Vec<(Language, f32)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let tag = params.next()?.trim();
+            let quality = match params.find_map(|param| param.trim().strip_prefix("q=")) {
+                Some(quality) => quality.trim().parse().ok()?,
+                None => 1.0,
+            };
+            let primary = tag.split('-').next()?.to_ascii_lowercase();
+            let language = Language::from_code(&primary)?;
+            (quality > 0.0).then_some((language, quality))
+        })
+        .collect();
+    // Stable, so ties keep the order of the header
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut languages = Vec::new();
+    for (language, _) in ranges {
+        if !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+    languages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Language::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for language in Language::ALL {
+            assert_eq!(Language::from_code(language.code()), Some(language));
+        }
+        assert_eq!(Language::from_code("EN"), None);
+        assert_eq!(Language::from_code("nl"), None);
+    }
+
+    #[test]
+    fn orders_by_quality() {
+        assert_eq!(preferred("fr;q=0.5, de, es;q=0.8"), [De, Es, Fr]);
+    }
+
+    #[test]
+    fn ties_keep_header_order() {
+        assert_eq!(preferred("it;q=0.7, en;q=0.7, pt"), [Pt, It, En]);
+    }
+
+    #[test]
+    fn maps_regional_variants() {
+        assert_eq!(preferred("pt-BR, EN-gb;q=0.9"), [Pt, En]);
+    }
+
+    #[test]
+    fn drops_refused_and_unknown_languages() {
+        assert_eq!(preferred("nl, fr;q=0, *;q=0.5, de;q=0.1"), [De]);
+        assert_eq!(preferred("en;q=bogus, es"), [Es]);
+        assert!(preferred("").is_empty());
+    }
+
+    #[test]
+    fn keeps_the_best_quality_of_a_language() {
+        assert_eq!(preferred("en-US;q=0.2, de;q=0.5, en;q=0.9"), [En, De]);
+    }
+}
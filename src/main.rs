@@ -8,8 +8,11 @@ use crate::setup::SetupResult;
 mod auth;
 mod config;
 mod connections;
+mod jobs;
+mod language;
 mod routes;
 mod setup;
+mod slug;
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
@@ -0,0 +1,229 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use migration::{Expr, SimpleExpr};
+use sea_orm::{Order, Value};
+use uuid::Uuid;
+
+/// Which side of the cursor post a page lies on, in newest first order
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Older posts, the next page
+    After,
+    /// Newer posts, the previous page
+    Before,
+}
+
+/// Position in the `(creation_time, id)` ordering of the posts listing,
+/// handed to clients as an opaque token
+pub struct Cursor {
+    pub direction: Direction,
+    pub creation_time: chrono::NaiveDateTime,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let direction = match self.direction {
+            Direction::After => 'a',
+            Direction::Before => 'b',
+        };
+        let token = format!(
+            "{direction}{}:{}",
+            self.creation_time.and_utc().timestamp_micros(),
+            self.id.simple()
+        );
+        URL_SAFE_NO_PAD.encode(token)
+    }
+
+    /// `None` for anything `encode` could not have produced
+    pub fn decode(token: &str) -> Option<Self> {
+        let token = String::from_utf8(URL_SAFE_NO_PAD.decode(token).ok()?).ok()?;
+        let direction = match token.get(..1)? {
+            "a" => Direction::After,
+            "b" => Direction::Before,
+            _ => return None,
+        };
+        let (micros, id) = token[1..].split_once(':')?;
+        let creation_time = chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?;
+        Some(Self {
+            direction,
+            creation_time: creation_time.naive_utc(),
+            id: Uuid::parse_str(id).ok()?,
+        })
+    }
+
+    /// Selects the posts on the cursor's side, excluding the cursor post itself
+    pub fn condition(&self) -> SimpleExpr {
+        let operator = match self.direction {
+            Direction::After => "<",
+            Direction::Before => ">",
+        };
+        Expr::cust_with_values(
+            format!(r#"("posts"."creation_time", "posts"."id") {operator} ($1, $2)"#),
+            [Value::from(self.creation_time), Value::from(self.id)],
+        )
+    }
+
+    /// Pages before the cursor are read in ascending order so the limit
+    /// keeps the posts closest to it, then reversed by `paginate`
+    pub fn order(&self) -> Order {
+        match self.direction {
+            Direction::After => Order::Desc,
+            Direction::Before => Order::Asc,
+        }
+    }
+}
+
+/// One page of the listing and the cursors of the pages around it
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+/// Turns rows fetched with `limit + 1` into a page in newest first order.
+/// `key` gives the `(creation_time, id)` of a row and `has_previous` whether
+/// an offset page has newer posts before it.
+pub fn paginate<T>(
+    mut rows: Vec<T>,
+    limit: u64,
+    cursor: Option<&Cursor>,
+    has_previous: bool,
+    key: impl Fn(&T) -> (chrono::NaiveDateTime, Uuid),
+) -> Page<T> {
+    let has_more = rows.len() as u64 > limit;
+    rows.truncate(limit as usize);
+
+    let backwards = cursor.is_some_and(|cursor| cursor.direction == Direction::Before);
+    let (has_next, has_prev) = if backwards {
+        rows.reverse();
+        (true, has_more)
+    } else {
+        (has_more, has_previous || cursor.is_some())
+    };
+
+    let cursor_at = |row: Option<&T>, direction| {
+        row.map(|row| {
+            let (creation_time, id) = key(row);
+            Cursor {
+                direction,
+                creation_time,
+                id,
+            }
+            .encode()
+        })
+    };
+    Page {
+        next_cursor: has_next
+            .then(|| cursor_at(rows.last(), Direction::After))
+            .flatten(),
+        prev_cursor: has_prev
+            .then(|| cursor_at(rows.first(), Direction::Before))
+            .flatten(),
+        items: rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(micros: i64) -> chrono::NaiveDateTime {
+        chrono::DateTime::from_timestamp_micros(micros)
+            .unwrap()
+            .naive_utc()
+    }
+
+    /// Rows as `(creation_time, id)`, newest first like the listing
+    fn rows(count: u128) -> Vec<(chrono::NaiveDateTime, Uuid)> {
+        (0..count)
+            .rev()
+            .map(|n| (at(1_700_000_000_000_000 + n as i64), Uuid::from_u128(n)))
+            .collect()
+    }
+
+    #[test]
+    fn decode_round_trips_encode() {
+        for direction in [Direction::After, Direction::Before] {
+            let cursor = Cursor {
+                direction,
+                creation_time: at(1_700_000_000_123_456),
+                id: Uuid::from_u128(42),
+            };
+            let decoded = Cursor::decode(&cursor.encode()).unwrap();
+            assert!(decoded.direction == direction);
+            assert_eq!(decoded.creation_time, cursor.creation_time);
+            assert_eq!(decoded.id, cursor.id);
+        }
+    }
+
+    #[test]
+    fn decode_rejects_bad_tokens() {
+        let bad = |token: &str| URL_SAFE_NO_PAD.encode(token);
+        for token in [
+            String::new(),
+            "not base64!".to_string(),
+            bad("x1:00000000000000000000000000000001"),
+            bad("a1"),
+            bad("anope:00000000000000000000000000000001"),
+            bad("a1:not-a-uuid"),
+            bad("a99999999999999999999:00000000000000000000000000000001"),
+        ] {
+            assert!(
+                Cursor::decode(&token).is_none(),
+                "{token} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn first_page_only_links_forward() {
+        let page = paginate(rows(4), 3, None, false, |row| *row);
+        assert_eq!(page.items.len(), 3);
+        assert!(page.prev_cursor.is_none());
+
+        let next = Cursor::decode(&page.next_cursor.unwrap()).unwrap();
+        assert!(next.direction == Direction::After);
+        assert_eq!(next.id, page.items[2].1);
+    }
+
+    #[test]
+    fn last_page_only_links_back() {
+        let cursor = Cursor {
+            direction: Direction::After,
+            creation_time: at(0),
+            id: Uuid::nil(),
+        };
+        let page = paginate(rows(2), 3, Some(&cursor), false, |row| *row);
+        assert!(page.next_cursor.is_none());
+
+        let prev = Cursor::decode(&page.prev_cursor.unwrap()).unwrap();
+        assert!(prev.direction == Direction::Before);
+        assert_eq!(prev.id, page.items[0].1);
+    }
+
+    #[test]
+    fn backwards_page_is_reversed_to_newest_first() {
+        let cursor = Cursor {
+            direction: Direction::Before,
+            creation_time: at(0),
+            id: Uuid::nil(),
+        };
+        // Read oldest first, as `Cursor::order` asks for
+        let mut ascending = rows(3);
+        ascending.reverse();
+        let page = paginate(ascending, 2, Some(&cursor), false, |row| *row);
+
+        assert_eq!(page.items, rows(3)[1..].to_vec());
+        assert!(page.next_cursor.is_some());
+        assert!(page.prev_cursor.is_some());
+    }
+
+    #[test]
+    fn empty_page_has_no_cursors() {
+        let page = paginate(rows(0), 3, None, true, |row| *row);
+        assert!(page.items.is_empty());
+        assert!(page.next_cursor.is_none());
+        assert!(page.prev_cursor.is_none());
+    }
+}
@@ -1,13 +1,19 @@
 
 use poem_openapi::{OpenApi, Tags};
 
-mod posts;
+mod cursor;
+pub(crate) mod posts;
+mod preconditions;
+pub(crate) mod revisions;
+mod search;
+pub(crate) mod tags;
 
 #[derive(Debug, Tags)]
 #[allow(dead_code)]
 pub enum ApiTags {
     Posts,
-
+    Revisions,
+    Tags,
 }
 
 pub struct RootApi;
@@ -21,5 +27,11 @@ impl RootApi {
 }
 
 pub fn api() -> impl OpenApi {
-    (RootApi, posts::PostsApi)
+    (
+        RootApi,
+        posts::PostsApi,
+        revisions::RevisionsApi,
+        tags::TagsApi,
+        tags::TagCollectionApi,
+    )
 }
\ No newline at end of file
@@ -1,22 +1,43 @@
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::Duration;
 
-use crate::auth::BearerAuthorization;
+use crate::auth::{BearerAuthorization, OptionalBearerAuthorization};
+use crate::config::CONFIG;
+use crate::language::{self, Language};
 use crate::routes::ApiTags;
+use crate::routes::cursor::{Cursor, paginate};
+use crate::routes::preconditions::{
+    VARY, VARY_LANGUAGE, cache_control, content_etag, http_date, if_match, not_modified, post_etag,
+    post_last_modified,
+};
+use crate::routes::revisions::record_revision;
+use crate::routes::search::{
+    DEFAULT_SIMILARITY, Facets, HighlightOptions, PostHighlight, QuerySyntax, facets, highlights,
+    invalid_query, is_timeout, like_prefix, resembles, set_similarity_threshold,
+    set_statement_timeout, similarity_rank,
+};
+use crate::routes::tags::{
+    display_name, in_category, normalized_name, resolve_tag, resolve_tag_ids,
+};
+use crate::slug;
 
+use entities::post_slug_history::Entity as PostSlugHistory;
 use entities::posts::Entity as Posts;
 use entities::sea_orm_active_enums::PostsStatusEnum;
-use migration::Expr;
+use migration::{Expr, SelectStatement};
 use poem::Error;
 use poem::error::BadRequest;
 use poem::{Result, error::InternalServerError, web::Data};
-use poem_openapi::param::Query;
+use poem_openapi::param::{Header, Query};
 use poem_openapi::payload::PlainText;
+use poem_openapi::types::MaybeUndefined;
 use poem_openapi::{ApiResponse, OpenApi, param::Path, payload::Json};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, ModelTrait, PaginatorTrait,
-    QueryFilter, QuerySelect, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, ModelTrait,
+    PaginatorTrait, QueryFilter, QuerySelect, QueryTrait, Set, TransactionTrait,
 };
-use sea_orm::{DatabaseConnection, QueryOrder, Value};
+use sea_orm::{DatabaseConnection, FromQueryResult, QueryOrder, Select, SqlErr};
 use uuid::Uuid;
 
 pub struct PostsApi;
@@ -34,27 +55,227 @@ pub struct PostWithTags {
     pub subheading: String,
     pub last_edit: Option<chrono::NaiveDateTime>,
     pub post_status: PostsStatusEnum,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+    /// ISO 639-1 code of the language the post is written in
+    pub language: String,
     pub tags: Vec<String>,
+    /// The same post in other languages, ordered by language
+    pub translations: Vec<Translation>,
+}
+
+impl PostWithTags {
+    fn new(
+        post: entities::posts::Model,
+        tags: Vec<String>,
+        translations: Vec<Translation>,
+    ) -> Self {
+        Self {
+            id: post.id,
+            slug: post.slug,
+            title: post.title,
+            hero_image: post.hero_image,
+            creation_time: post.creation_time,
+            body: post.body,
+            author: post.author,
+            created_by: post.created_by,
+            subheading: post.subheading,
+            last_edit: post.last_edit,
+            post_status: post.post_status,
+            publish_at: post.publish_at,
+            unpublish_at: post.unpublish_at,
+            language: post.language,
+            tags,
+            translations,
+        }
+    }
+}
+
+/// Where to read a post in another language
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct Translation {
+    pub language: String,
+    pub slug: String,
+}
+
+#[derive(FromQueryResult)]
+struct TranslationRow {
+    id: Uuid,
+    translation_group_id: Uuid,
+    language: String,
+    slug: String,
 }
 
 #[derive(ApiResponse)]
+#[allow(clippy::large_enum_variant)]
 enum GetPostResponse {
     #[oai(status = 200)]
-    Ok(Json<PostWithTags>),
+    Ok(
+        Json<PostWithTags>,
+        #[oai(header = "ETag")] String,
+        #[oai(header = "Last-Modified")] String,
+        #[oai(header = "Cache-Control")] String,
+        #[oai(header = "Vary")] String,
+        #[oai(header = "Content-Language")] String,
+    ),
+    #[oai(status = 304)]
+    NotModified(
+        #[oai(header = "ETag")] String,
+        #[oai(header = "Cache-Control")] String,
+        #[oai(header = "Vary")] String,
+    ),
+    /// The slug was retired, the body and `Location` hold the current one
+    #[oai(status = 301)]
+    MovedPermanently(PlainText<String>, #[oai(header = "Location")] String),
     #[oai(status = 404)]
     NotFound,
 }
 
+/// Everything a post listing needs, without the body
 #[derive(serde::Serialize, poem_openapi::Object)]
+pub struct PostSummary {
+    pub slug: String,
+    pub title: String,
+    pub subheading: String,
+    pub hero_image: Option<String>,
+    pub author: String,
+    pub creation_time: chrono::NaiveDateTime,
+    pub post_status: PostsStatusEnum,
+    pub tags: Vec<String>,
+    pub reading_time_minutes: u32,
+}
+
+#[derive(FromQueryResult)]
+struct PostSummaryRow {
+    id: Uuid,
+    slug: String,
+    title: String,
+    subheading: String,
+    hero_image: Option<String>,
+    author: String,
+    creation_time: chrono::NaiveDateTime,
+    post_status: PostsStatusEnum,
+    word_count: i32,
+}
+
+#[derive(Default, serde::Serialize, poem_openapi::Object)]
 pub struct GetPostsResponse {
     pub posts: Vec<String>,
     pub count: Option<u64>,
+    /// Full posts, in the same order as `posts`, when requested with `view=full`
+    pub details: Option<Vec<PostWithTags>>,
+    /// Post summaries, in the same order as `posts`, when requested with `view=summary`
+    pub summaries: Option<Vec<PostSummary>>,
+    /// Matched words of each post marked in its title and body, in the same order
+    /// as `posts`, when searching with `q`
+    pub highlights: Option<Vec<PostHighlight>>,
+    /// Counts over every matching post, not just this page, when requested with `facets`
+    pub facets: Option<Box<Facets>>,
+    /// True when nothing matched the search exactly and the posts are similarity matches
+    pub fuzzy: bool,
+    /// Pass as `cursor` to get the page of older posts, absent on the last page
+    pub next_cursor: Option<String>,
+    /// Pass as `cursor` to get the page of newer posts, absent on the first page
+    pub prev_cursor: Option<String>,
+}
+
+/// What `get_posts` returns for each post besides its slug
+#[derive(poem_openapi::Enum, Clone, Copy, PartialEq, Eq)]
+#[oai(rename_all = "lowercase")]
+enum PostsView {
+    Slugs,
+    Summary,
+    Full,
+}
+
+/// Order of the posts returned by `get_posts`
+#[derive(poem_openapi::Enum, Clone, Copy, PartialEq, Eq)]
+#[oai(rename_all = "lowercase")]
+enum PostsSort {
+    /// Most recently created first
+    Newest,
+    /// Best match for the search query `q` first
+    Relevance,
+}
+
+/// A post whose title starts with the typed prefix
+#[derive(serde::Serialize, poem_openapi::Object, FromQueryResult)]
+pub struct Suggestion {
+    pub slug: String,
+    pub title: String,
+}
+
+#[derive(ApiResponse)]
+enum SuggestResponse {
+    #[oai(status = 200)]
+    Ok(
+        Json<Vec<Suggestion>>,
+        #[oai(header = "Cache-Control")] String,
+    ),
+}
+
+const DEFAULT_SUGGESTIONS: u64 = 8;
+const MAX_SUGGESTIONS: u64 = 20;
+
+const DEFAULT_PAGE_SIZE: u64 = 20;
+const MAX_PAGE_SIZE: u64 = 100;
+
+/// How the `tags` filter of `get_posts` combines several tags
+#[derive(poem_openapi::Enum, Clone, Copy, PartialEq, Eq)]
+#[oai(rename_all = "lowercase")]
+enum TagMode {
+    /// Posts with at least one of the tags
+    Any,
+    /// Posts with every one of the tags
+    All,
+}
+
+const WORDS_PER_MINUTE: u32 = 200;
+
+#[derive(ApiResponse)]
+enum ListPostsResponse {
+    /// No `Last-Modified`, a post leaving the result would not move it forward
+    #[oai(status = 200)]
+    Ok(
+        Json<GetPostsResponse>,
+        #[oai(header = "ETag")] String,
+        #[oai(header = "Cache-Control")] String,
+        #[oai(header = "Vary")] String,
+    ),
+    #[oai(status = 304)]
+    NotModified(
+        #[oai(header = "ETag")] String,
+        #[oai(header = "Cache-Control")] String,
+        #[oai(header = "Vary")] String,
+    ),
+    #[oai(status = 400)]
+    BadRequest(PlainText<String>),
+}
+
+#[derive(ApiResponse)]
+enum BulkGetResponse {
+    /// No `Last-Modified`, a post leaving the result would not move it forward
+    #[oai(status = 200)]
+    Ok(
+        Json<Vec<PostWithTags>>,
+        #[oai(header = "ETag")] String,
+        #[oai(header = "Cache-Control")] String,
+        #[oai(header = "Vary")] String,
+    ),
+    #[oai(status = 304)]
+    NotModified(
+        #[oai(header = "ETag")] String,
+        #[oai(header = "Cache-Control")] String,
+        #[oai(header = "Vary")] String,
+    ),
 }
 
 #[derive(poem_openapi::Object)]
 struct InsertPostRequest {
     #[oai(validator(min_length = 10))]
     pub title: String,
+    /// Generated from the title when omitted
+    pub slug: Option<String>,
     #[oai(validator(min_length = 3))]
     pub author: String,
     pub body: String,
@@ -63,6 +284,14 @@ struct InsertPostRequest {
     pub tags: Option<Vec<String>>,
     #[oai(validator(min_length = 5))]
     pub hero_image: Option<String>,
+    /// UTC instant at which a draft gets published
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    /// UTC instant at which a published post gets archived
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+    /// Defaults to English, decides how the post is stemmed for search
+    pub language: Option<Language>,
+    /// Slug of a post in another language this one is a translation of
+    pub translation_of: Option<String>,
 }
 
 #[derive(ApiResponse)]
@@ -79,6 +308,8 @@ enum InsertPostResponse {
 struct PatchPostRequest {
     #[oai(validator(min_length = 10))]
     pub title: Option<String>,
+    /// Regenerated from the title when the title changes and this is omitted
+    pub slug: Option<String>,
     #[oai(validator(min_length = 3))]
     pub author: Option<String>,
     pub body: Option<String>,
@@ -88,18 +319,52 @@ struct PatchPostRequest {
     pub tags: Option<Vec<String>>,
     #[oai(validator(min_length = 5))]
     pub hero_image: Option<String>,
+    /// Send `null` to cancel a scheduled publication
+    pub publish_at: MaybeUndefined<chrono::NaiveDateTime>,
+    /// Send `null` to cancel a scheduled unpublication
+    pub unpublish_at: MaybeUndefined<chrono::NaiveDateTime>,
+    pub language: Option<Language>,
+    /// Slug of a post to become a translation of, send `null` to stop being a translation
+    pub translation_of: MaybeUndefined<String>,
 }
 
 #[derive(ApiResponse)]
 enum PatchPostResponse {
     #[oai(status = 200)]
-    Ok(PlainText<String>),
+    Ok(PlainText<String>, #[oai(header = "ETag")] String),
     #[oai(status = 404)]
     NotFound,
     #[oai(status = 409)]
     Conflict,
     #[oai(status = 400)]
     BadRequest(PlainText<String>),
+    /// `If-Match` does not match the current version of the post
+    #[oai(status = 412)]
+    PreconditionFailed,
+}
+
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct TrashedPost {
+    pub slug: String,
+    pub title: String,
+    pub author: String,
+    pub post_status: PostsStatusEnum,
+    pub deleted_at: chrono::NaiveDateTime,
+}
+
+#[derive(ApiResponse)]
+enum ListTrashResponse {
+    #[oai(status = 200)]
+    Ok(Json<Vec<TrashedPost>>),
+}
+
+#[derive(ApiResponse)]
+enum TrashActionResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    /// No post with this slug is in the trash
+    #[oai(status = 404)]
+    NotFound,
 }
 
 #[derive(ApiResponse)]
@@ -108,78 +373,651 @@ enum DeletePostResponse {
     Ok(PlainText<String>),
     #[oai(status = 404)]
     NotFound,
+    /// `If-Match` does not match the current version of the post
+    #[oai(status = 412)]
+    PreconditionFailed,
 }
 
-async fn create_tag_if_not_exists<T: ConnectionTrait>(
+/// Locks a post in the trash for restoring or purging
+async fn find_trashed_post<T: ConnectionTrait>(
     db: &T,
-    tag_name: &str,
-) -> Result<entities::tags::Model, Error> {
-    let tag = entities::tags::Entity::find()
-        .filter(entities::tags::Column::Name.eq(tag_name))
+    post_slug: &str,
+) -> Result<Option<entities::posts::Model>> {
+    Posts::find()
+        .filter(entities::posts::Column::Slug.eq(post_slug))
+        .filter(entities::posts::Column::DeletedAt.is_not_null())
+        .lock_exclusive()
         .one(db)
         .await
-        .map_err(InternalServerError)?;
+        .map_err(InternalServerError)
+}
+
+/// Restricts a posts query to the posts the caller is allowed to read.
+/// Trashed posts are never readable. Anonymous callers only get published posts,
+/// `read:draft` holders get every post and authenticated users always get the posts
+/// they created.
+pub(crate) fn readable_by(auth: &OptionalBearerAuthorization) -> Condition {
+    let published = entities::posts::Column::PostStatus.eq(PostsStatusEnum::Published);
+    let visible = match auth.claims() {
+        Some(claims) if claims.has_permission("read", "draft") => Condition::all(),
+        Some(claims) => match Uuid::from_str(&claims.sub) {
+            Ok(user_id) => Condition::any()
+                .add(published)
+                .add(entities::posts::Column::CreatedBy.eq(user_id)),
+            Err(_) => Condition::all().add(published),
+        },
+        None => Condition::all().add(published),
+    };
+    Condition::all()
+        .add(entities::posts::Column::DeletedAt.is_null())
+        .add(visible)
+}
+
+/// Ids of the posts carrying the tags, any or all of them depending on `mode`
+fn tagged_post_ids(tag_ids: &[Uuid], mode: TagMode) -> SelectStatement {
+    let mut tag_ids = tag_ids.to_vec();
+    tag_ids.sort();
+    tag_ids.dedup();
 
-    if let Some(tag) = tag {
-        return Ok(tag);
+    let mut query = entities::post_tags::Entity::find()
+        .select_only()
+        .column(entities::post_tags::Column::PostId)
+        .filter(entities::post_tags::Column::TagId.is_in(tag_ids.clone()));
+    if mode == TagMode::All {
+        query = query.group_by(entities::post_tags::Column::PostId).having(
+            Expr::expr(Expr::col(entities::post_tags::Column::TagId).count_distinct())
+                .eq(tag_ids.len() as i64),
+        );
+    }
+    query.into_query()
+}
+
+/// Finds the tag a client supplied name refers to, creating it when neither
+/// a tag nor an alias matches the name's normalized form
+pub(crate) async fn create_tag_if_not_exists<T: ConnectionTrait>(
+    db: &T,
+    tag_name: &str,
+) -> Result<entities::tags::Model, Error> {
+    // Held until the caller's transaction ends, so the orphan tag cleanup can't delete
+    // the tag before its post_tags row is written. A tag deleted just before is created
+    // again below.
+    if let Some(tag) = resolve_tag(db, tag_name).await? {
+        let locked = entities::tags::Entity::find_by_id(tag.id)
+            .lock_shared()
+            .one(db)
+            .await
+            .map_err(InternalServerError)?;
+        if let Some(tag) = locked {
+            return Ok(tag);
+        }
+    }
+
+    let name = display_name(tag_name);
+    let normalized = normalized_name(&name);
+    if normalized.is_empty() {
+        return Err(Error::from_string(
+            "Tag names cannot be empty".to_string(),
+            poem::http::StatusCode::BAD_REQUEST,
+        ));
     }
 
     let tag = entities::tags::ActiveModel {
         id: Set(Uuid::new_v4()),
-        name: Set(tag_name.to_string()),
+        name: Set(name),
+        normalized_name: Set(normalized),
+        parent_id: Set(None),
     };
     tag.insert(db).await.map_err(InternalServerError)
 }
 
+/// Returns true when `slug` belongs to a post other than `post_id`,
+/// either as its current slug or as one it used before, or is reserved
+pub(crate) async fn slug_taken<T: ConnectionTrait>(
+    db: &T,
+    slug: &str,
+    post_id: Uuid,
+) -> Result<bool, Error> {
+    if slug::is_reserved(slug) {
+        return Ok(true);
+    }
+
+    let existing_post = Posts::find()
+        .filter(entities::posts::Column::Slug.eq(slug))
+        .one(db)
+        .await
+        .map_err(InternalServerError)?;
+    if existing_post.is_some_and(|existing_post| existing_post.id != post_id) {
+        return Ok(true);
+    }
+
+    let retired_slug = PostSlugHistory::find_by_id(slug)
+        .one(db)
+        .await
+        .map_err(InternalServerError)?;
+    Ok(retired_slug.is_some_and(|retired_slug| retired_slug.post_id != post_id))
+}
+
+/// First free slug among `base`, `base_2`, `base_3`...
+pub(crate) async fn unique_slug<T: ConnectionTrait>(
+    db: &T,
+    base: &str,
+    post_id: Uuid,
+) -> Result<String, Error> {
+    let mut slug = base.to_string();
+    let mut suffix = 1;
+    while slug_taken(db, &slug, post_id).await? {
+        suffix += 1;
+        slug = slug::with_suffix(base, suffix);
+    }
+    Ok(slug)
+}
+
+/// Records `old_slug` as retired so requests for it can be redirected to `new_slug`
+pub(crate) async fn retire_slug<T: ConnectionTrait>(
+    db: &T,
+    post_id: Uuid,
+    old_slug: &str,
+    new_slug: &str,
+) -> Result<(), Error> {
+    if old_slug == new_slug {
+        return Ok(());
+    }
+
+    // The post may be taking back one of its own retired slugs
+    PostSlugHistory::delete_many()
+        .filter(entities::post_slug_history::Column::Slug.eq(new_slug))
+        .filter(entities::post_slug_history::Column::PostId.eq(post_id))
+        .exec(db)
+        .await
+        .map_err(InternalServerError)?;
+
+    let retired_slug = entities::post_slug_history::ActiveModel {
+        slug: Set(old_slug.to_string()),
+        post_id: Set(post_id),
+        retired_at: Set(chrono::Utc::now().naive_utc()),
+    };
+    retired_slug.insert(db).await.map_err(InternalServerError)?;
+    Ok(())
+}
+
+/// Tag names of every post in `post_ids`, loaded with a single query
+pub(crate) async fn tag_names_by_post<T: ConnectionTrait>(
+    db: &T,
+    post_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<String>>, Error> {
+    let mut tag_names: HashMap<Uuid, Vec<String>> = HashMap::new();
+    if post_ids.is_empty() {
+        return Ok(tag_names);
+    }
+
+    let post_tags = entities::post_tags::Entity::find()
+        .filter(entities::post_tags::Column::PostId.is_in(post_ids.to_vec()))
+        .find_also_related(entities::tags::Entity)
+        .order_by_asc(entities::tags::Column::Name)
+        .all(db)
+        .await
+        .map_err(InternalServerError)?;
+    for (post_tag, tag) in post_tags {
+        if let Some(tag) = tag {
+            tag_names
+                .entry(post_tag.post_id)
+                .or_default()
+                .push(tag.name);
+        }
+    }
+    Ok(tag_names)
+}
+
+/// Readable posts of every translation group in `group_ids`, loaded with a single query
+async fn translations_by_group<T: ConnectionTrait>(
+    db: &T,
+    auth: &OptionalBearerAuthorization,
+    group_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<TranslationRow>>, Error> {
+    let mut translations: HashMap<Uuid, Vec<TranslationRow>> = HashMap::new();
+    if group_ids.is_empty() {
+        return Ok(translations);
+    }
+
+    let rows: Vec<TranslationRow> = Posts::find()
+        .select_only()
+        .column(entities::posts::Column::Id)
+        .column(entities::posts::Column::TranslationGroupId)
+        .column(entities::posts::Column::Language)
+        .column(entities::posts::Column::Slug)
+        .filter(entities::posts::Column::TranslationGroupId.is_in(group_ids.to_vec()))
+        .filter(readable_by(auth))
+        .order_by_asc(entities::posts::Column::Language)
+        .into_model()
+        .all(db)
+        .await
+        .map_err(InternalServerError)?;
+    for row in rows {
+        translations
+            .entry(row.translation_group_id)
+            .or_default()
+            .push(row);
+    }
+    Ok(translations)
+}
+
+/// Attaches tags and the translations `auth` can read to `posts`, keeping their order
+pub(crate) async fn posts_with_tags<T: ConnectionTrait>(
+    db: &T,
+    auth: &OptionalBearerAuthorization,
+    posts: Vec<entities::posts::Model>,
+) -> Result<Vec<PostWithTags>, Error> {
+    let post_ids: Vec<Uuid> = posts.iter().map(|post| post.id).collect();
+    let mut tag_names = tag_names_by_post(db, &post_ids).await?;
+    let group_ids: Vec<Uuid> = posts.iter().map(|post| post.translation_group_id).collect();
+    let translations = translations_by_group(db, auth, &group_ids).await?;
+    Ok(posts
+        .into_iter()
+        .map(|post| {
+            let tags = tag_names.remove(&post.id).unwrap_or_default();
+            let translations = translations
+                .get(&post.translation_group_id)
+                .into_iter()
+                .flatten()
+                .filter(|translation| translation.id != post.id)
+                .map(|translation| Translation {
+                    language: translation.language.clone(),
+                    slug: translation.slug.clone(),
+                })
+                .collect();
+            PostWithTags::new(post, tags, translations)
+        })
+        .collect())
+}
+
+/// Posts other than `post_id` in the translation group `group_id`, trashed ones aside
+async fn other_translations<T: ConnectionTrait>(
+    db: &T,
+    group_id: Uuid,
+    post_id: Uuid,
+) -> Result<Vec<entities::posts::Model>, Error> {
+    Posts::find()
+        .filter(entities::posts::Column::TranslationGroupId.eq(group_id))
+        .filter(entities::posts::Column::Id.ne(post_id))
+        .filter(entities::posts::Column::DeletedAt.is_null())
+        .all(db)
+        .await
+        .map_err(InternalServerError)
+}
+
+/// Bumps `last_edit` of the posts of `group_id` other than `post_id`, whose
+/// `translations` list, and with it their ETag, changes along with `post_id`
+async fn touch_translations<T: ConnectionTrait>(
+    db: &T,
+    group_id: Uuid,
+    post_id: Uuid,
+) -> Result<(), DbErr> {
+    Posts::update_many()
+        .col_expr(
+            entities::posts::Column::LastEdit,
+            Expr::value(chrono::Utc::now().naive_utc()),
+        )
+        .filter(entities::posts::Column::TranslationGroupId.eq(group_id))
+        .filter(entities::posts::Column::Id.ne(post_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Touches the translations of a post updated from `old` to `new`, in both its old and
+/// new group, when the update shows in their `translations` lists
+pub(crate) async fn touch_changed_translations<T: ConnectionTrait>(
+    db: &T,
+    old: &entities::posts::Model,
+    new: &entities::posts::Model,
+) -> Result<(), DbErr> {
+    let listed_the_same = old.slug == new.slug
+        && old.language == new.language
+        && old.post_status == new.post_status
+        && old.deleted_at == new.deleted_at
+        && old.translation_group_id == new.translation_group_id;
+    if listed_the_same {
+        return Ok(());
+    }
+
+    touch_translations(db, new.translation_group_id, new.id).await?;
+    if old.translation_group_id != new.translation_group_id {
+        touch_translations(db, old.translation_group_id, new.id).await?;
+    }
+    Ok(())
+}
+
+/// Whether a write failed on a unique index, e.g. a slug or a translation language
+/// taken by a concurrent request
+fn is_unique_violation(err: &DbErr) -> bool {
+    matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_)))
+}
+
+/// Translation group a new translation of the post at `slug` joins
+async fn translation_group<T: ConnectionTrait>(db: &T, slug: &str) -> Result<Option<Uuid>, Error> {
+    Posts::find()
+        .select_only()
+        .column(entities::posts::Column::TranslationGroupId)
+        .filter(entities::posts::Column::Slug.eq(slug))
+        .filter(entities::posts::Column::DeletedAt.is_null())
+        .into_tuple()
+        .one(db)
+        .await
+        .map_err(InternalServerError)
+}
+
+/// The translation of `post` asked for with `lang` or, without it, `Accept-Language`.
+/// Falls back to the default language when none of the asked for languages is
+/// available, and to `post` itself when that isn't either or nothing was asked for.
+async fn negotiate_translation<T: ConnectionTrait>(
+    db: &T,
+    auth: &OptionalBearerAuthorization,
+    post: entities::posts::Model,
+    lang: Option<Language>,
+    accept_language: Option<&str>,
+) -> Result<entities::posts::Model, Error> {
+    let mut wanted = match (lang, accept_language) {
+        (Some(lang), _) => vec![lang],
+        (None, Some(accept_language)) => language::preferred(accept_language),
+        (None, None) => return Ok(post),
+    };
+    if wanted
+        .first()
+        .is_some_and(|first| first.code() == post.language)
+    {
+        return Ok(post);
+    }
+    wanted.push(Language::default());
+
+    let mut translations = Posts::find()
+        .filter(entities::posts::Column::TranslationGroupId.eq(post.translation_group_id))
+        .filter(readable_by(auth))
+        .all(db)
+        .await
+        .map_err(InternalServerError)?;
+    for language in wanted {
+        if let Some(index) = translations
+            .iter()
+            .position(|translation| translation.language == language.code())
+        {
+            return Ok(translations.swap_remove(index));
+        }
+    }
+    Ok(post)
+}
+
+async fn summary_rows<T: ConnectionTrait>(
+    db: &T,
+    query: Select<Posts>,
+) -> Result<Vec<PostSummaryRow>, Error> {
+    query
+        .select_only()
+        .columns([
+            entities::posts::Column::Id,
+            entities::posts::Column::Slug,
+            entities::posts::Column::Title,
+            entities::posts::Column::Subheading,
+            entities::posts::Column::HeroImage,
+            entities::posts::Column::Author,
+            entities::posts::Column::CreationTime,
+            entities::posts::Column::PostStatus,
+        ])
+        .column_as(
+            Expr::cust(
+                r#"COALESCE(array_length(regexp_split_to_array(btrim("posts"."body"), '\s+'), 1), 0)"#,
+            ),
+            "word_count",
+        )
+        .into_model::<PostSummaryRow>()
+        .all(db)
+        .await
+        .map_err(InternalServerError)
+}
+
+async fn post_summaries<T: ConnectionTrait>(
+    db: &T,
+    rows: Vec<PostSummaryRow>,
+) -> Result<Vec<PostSummary>, Error> {
+    let post_ids: Vec<Uuid> = rows.iter().map(|row| row.id).collect();
+    let mut tag_names = tag_names_by_post(db, &post_ids).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| PostSummary {
+            tags: tag_names.remove(&row.id).unwrap_or_default(),
+            reading_time_minutes: (row.word_count.max(0) as u32)
+                .div_ceil(WORDS_PER_MINUTE)
+                .max(1),
+            slug: row.slug,
+            title: row.title,
+            subheading: row.subheading,
+            hero_image: row.hero_image,
+            author: row.author,
+            creation_time: row.creation_time,
+            post_status: row.post_status,
+        })
+        .collect())
+}
+
+pub(crate) async fn post_tag_names<T: ConnectionTrait>(
+    db: &T,
+    post: &entities::posts::Model,
+) -> Result<Vec<String>, Error> {
+    let tags: Vec<entities::tags::Model> = post
+        .find_related(entities::tags::Entity)
+        .all(db)
+        .await
+        .map_err(InternalServerError)?;
+    Ok(tags.into_iter().map(|tag| tag.name).collect())
+}
+
+/// Replaces every tag of the post, creating missing tags on the way.
+/// Returns the names of the tags now attached to the post.
+pub(crate) async fn set_post_tags<T: ConnectionTrait>(
+    db: &T,
+    post_id: Uuid,
+    tags: &[String],
+) -> Result<Vec<String>, Error> {
+    entities::post_tags::Entity::delete_many()
+        .filter(entities::post_tags::Column::PostId.eq(post_id))
+        .exec(db)
+        .await
+        .map_err(InternalServerError)?;
+
+    let mut tag_ids = Vec::new();
+    let mut tag_names = Vec::new();
+    for tag in tags {
+        let tag = create_tag_if_not_exists(db, tag).await?;
+        if tag_ids.contains(&tag.id) {
+            continue;
+        }
+        let post_tag = entities::post_tags::ActiveModel {
+            post_id: Set(post_id),
+            tag_id: Set(tag.id),
+        };
+        post_tag.insert(db).await.map_err(InternalServerError)?;
+        tag_ids.push(tag.id);
+        tag_names.push(tag.name);
+    }
+    Ok(tag_names)
+}
+
+fn valid_schedule(
+    publish_at: Option<chrono::NaiveDateTime>,
+    unpublish_at: Option<chrono::NaiveDateTime>,
+) -> bool {
+    match (publish_at, unpublish_at) {
+        (Some(publish_at), Some(unpublish_at)) => publish_at < unpublish_at,
+        _ => true,
+    }
+}
+
+/// Stamps `published_at` the first time a post becomes published
+pub(crate) fn stamp_publication(
+    post: &mut entities::posts::ActiveModel,
+    now: chrono::NaiveDateTime,
+) {
+    if *post.post_status.as_ref() == PostsStatusEnum::Published
+        && post.published_at.as_ref().is_none()
+    {
+        post.published_at = Set(Some(now));
+    }
+}
+
+/// Compares tag names the way `resolve_tag` matches them
+fn same_tags(a: &[String], b: &[String]) -> bool {
+    let mut a: Vec<String> = a.iter().map(|name| normalized_name(name)).collect();
+    let mut b: Vec<String> = b.iter().map(|name| normalized_name(name)).collect();
+    a.sort();
+    a.dedup();
+    b.sort();
+    b.dedup();
+    a == b
+}
+
 #[OpenApi(prefix_path = "/posts", tag = "ApiTags::Posts")]
 impl PostsApi {
+    /// Published posts whose title starts with `prefix`, for type-ahead search.
+    /// Gives up after `SUGGEST_TIMEOUT_MS` and answers with no suggestions instead.
+    #[oai(method = "get", path = "/suggest")]
+    async fn suggest(
+        &self,
+        db: Data<&DatabaseConnection>,
+        prefix: Query<String>,
+        limit: Query<Option<u64>>,
+    ) -> Result<SuggestResponse> {
+        let prefix = prefix.0.trim().to_lowercase();
+        if prefix.is_empty() {
+            return Ok(SuggestResponse::Ok(
+                Json(Vec::new()),
+                CONFIG.cache_control.clone(),
+            ));
+        }
+        let limit = limit.0.unwrap_or(DEFAULT_SUGGESTIONS).min(MAX_SUGGESTIONS);
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+        set_statement_timeout(&tnx, Duration::from_millis(CONFIG.suggest_timeout_ms)).await?;
+
+        let suggestions = Posts::find()
+            .select_only()
+            .column(entities::posts::Column::Slug)
+            .column(entities::posts::Column::Title)
+            // Spelled out as literals so the planner can use the partial prefix index
+            .filter(Expr::cust(
+                r#""posts"."post_status" = 'published' AND "posts"."deleted_at" IS NULL"#,
+            ))
+            .filter(Expr::cust_with_values(
+                r#"lower("posts"."title") LIKE $1"#,
+                [like_prefix(&prefix)],
+            ))
+            .order_by_asc(Expr::cust(r#"lower("posts"."title")"#))
+            .limit(limit)
+            .into_model::<Suggestion>()
+            .all(&tnx)
+            .await;
+
+        match suggestions {
+            Ok(suggestions) => {
+                tnx.commit().await.map_err(InternalServerError)?;
+                Ok(SuggestResponse::Ok(
+                    Json(suggestions),
+                    CONFIG.cache_control.clone(),
+                ))
+            }
+            Err(err) if is_timeout(&err) => Ok(SuggestResponse::Ok(
+                Json(Vec::new()),
+                "no-store".to_string(),
+            )),
+            Err(err) => Err(InternalServerError(err)),
+        }
+    }
+
     #[oai(method = "get", path = "/:post_slug")]
+    #[allow(clippy::too_many_arguments)]
     async fn get_post(
         &self,
         post_slug: Path<String>,
+        #[oai(name = "If-None-Match")] if_none_match: Header<Option<String>>,
+        #[oai(name = "If-Modified-Since")] if_modified_since: Header<Option<String>>,
+        #[oai(name = "Accept-Language")] accept_language: Header<Option<String>>,
+        auth: OptionalBearerAuthorization,
         db: Data<&DatabaseConnection>,
+        lang: Query<Option<Language>>,
     ) -> Result<GetPostResponse> {
         let post: Option<entities::posts::Model> = Posts::find()
-            .filter(entities::posts::Column::Slug.eq(post_slug.0))
+            .filter(entities::posts::Column::Slug.eq(&post_slug.0))
+            .filter(readable_by(&auth))
             .one(*db)
             .await
             .map_err(InternalServerError)?;
 
         match post {
             Some(post) => {
-                // Fetch tags for this post
-                let tags: Vec<entities::tags::Model> = post
-                    .find_related(entities::tags::Entity)
-                    .all(*db)
-                    .await
-                    .map_err(InternalServerError)?;
+                let post =
+                    negotiate_translation(*db, &auth, post, lang.0, accept_language.0.as_deref())
+                        .await?;
+                let etag = post_etag(&post);
+                let last_modified = post_last_modified(&post);
+                let cache_control = cache_control(&auth);
+                if not_modified(
+                    if_none_match.0.as_deref(),
+                    if_modified_since.0.as_deref(),
+                    &etag,
+                    Some(last_modified),
+                ) {
+                    return Ok(GetPostResponse::NotModified(
+                        etag,
+                        cache_control,
+                        VARY_LANGUAGE.to_string(),
+                    ));
+                }
 
-                let tag_names: Vec<String> = tags.into_iter().map(|tag| tag.name).collect();
+                let post_with_tags = posts_with_tags(*db, &auth, vec![post])
+                    .await?
+                    .pop()
+                    .expect("one post in, one post out");
+                let content_language = post_with_tags.language.clone();
 
-                let post_with_tags = PostWithTags {
-                    id: post.id,
-                    slug: post.slug,
-                    title: post.title,
-                    hero_image: post.hero_image,
-                    creation_time: post.creation_time,
-                    body: post.body,
-                    author: post.author,
-                    created_by: post.created_by,
-                    subheading: post.subheading,
-                    last_edit: post.last_edit,
-                    post_status: post.post_status,
-                    tags: tag_names,
+                Ok(GetPostResponse::Ok(
+                    Json(post_with_tags),
+                    etag,
+                    http_date(last_modified),
+                    cache_control,
+                    VARY_LANGUAGE.to_string(),
+                    content_language,
+                ))
+            }
+            None => {
+                // The slug may have been retired by a title change
+                let retired_slug = PostSlugHistory::find_by_id(post_slug.0)
+                    .one(*db)
+                    .await
+                    .map_err(InternalServerError)?;
+                let Some(retired_slug) = retired_slug else {
+                    return Ok(GetPostResponse::NotFound);
                 };
 
-                return Ok(GetPostResponse::Ok(Json(post_with_tags)));
+                let post = Posts::find_by_id(retired_slug.post_id)
+                    .filter(readable_by(&auth))
+                    .one(*db)
+                    .await
+                    .map_err(InternalServerError)?;
+                match post {
+                    Some(post) => Ok(GetPostResponse::MovedPermanently(
+                        PlainText(post.slug.clone()),
+                        format!("/posts/{}", post.slug),
+                    )),
+                    None => Ok(GetPostResponse::NotFound),
+                }
             }
-            None => return Ok(GetPostResponse::NotFound),
         }
     }
 
     #[oai(method = "get", path = "/")]
+    #[allow(clippy::too_many_arguments)]
     async fn get_posts(
         &self,
+        #[oai(name = "If-None-Match")] if_none_match: Header<Option<String>>,
+        auth: OptionalBearerAuthorization,
         db: Data<&DatabaseConnection>,
         limit: Query<Option<u64>>,
         offset: Query<Option<u64>>,
@@ -188,96 +1026,312 @@ impl PostsApi {
         add_count: Query<Option<bool>>,
         creation_time: Query<Option<String>>,
         status: Query<Option<PostsStatusEnum>>,
-    ) -> Result<Json<GetPostsResponse>> {
-        let limit = limit.0.unwrap_or(20);
+        view: Query<Option<PostsView>>,
+        cursor: Query<Option<String>>,
+        tags: Query<Option<Vec<String>>>,
+        tag_mode: Query<Option<TagMode>>,
+        exclude_tags: Query<Option<Vec<String>>>,
+        category: Query<Option<String>>,
+        q: Query<Option<String>>,
+        sort: Query<Option<PostsSort>>,
+        highlight_start: Query<Option<String>>,
+        highlight_stop: Query<Option<String>>,
+        highlight_fragments: Query<Option<u32>>,
+        syntax: Query<Option<QuerySyntax>>,
+        fuzzy: Query<Option<bool>>,
+        similarity: Query<Option<f64>>,
+        language: Query<Option<Language>>,
+        #[oai(name = "facets")] with_facets: Query<Option<bool>>,
+    ) -> Result<ListPostsResponse> {
+        let limit = limit.0.unwrap_or(DEFAULT_PAGE_SIZE);
+        if limit > MAX_PAGE_SIZE {
+            return Ok(ListPostsResponse::BadRequest(PlainText(format!(
+                "limit cannot be more than {MAX_PAGE_SIZE}"
+            ))));
+        }
+        let highlight_options =
+            match HighlightOptions::new(highlight_start.0, highlight_stop.0, highlight_fragments.0)
+            {
+                Ok(options) => options,
+                Err(err) => return Ok(ListPostsResponse::BadRequest(PlainText(err.to_string()))),
+            };
+        let sort = sort.0.unwrap_or(PostsSort::Newest);
+        if sort == PostsSort::Relevance {
+            if q.0.is_none() {
+                return Ok(ListPostsResponse::BadRequest(PlainText(
+                    "sort=relevance needs a search query q".to_string(),
+                )));
+            }
+            if cursor.0.is_some() {
+                return Ok(ListPostsResponse::BadRequest(PlainText(
+                    "cursor pagination is only available with sort=newest".to_string(),
+                )));
+            }
+        }
+        let cursor = match &cursor.0 {
+            Some(_) if offset.0.is_some() => {
+                return Ok(ListPostsResponse::BadRequest(PlainText(
+                    "cursor and offset cannot be combined".to_string(),
+                )));
+            }
+            Some(token) => match Cursor::decode(token) {
+                Some(cursor) => Some(cursor),
+                None => {
+                    return Ok(ListPostsResponse::BadRequest(PlainText(
+                        "Invalid cursor".to_string(),
+                    )));
+                }
+            },
+            None => None,
+        };
         let offset = offset.0.unwrap_or(0);
-        let mut query = Posts::find();
-        if let Some(title) = &title.0 {
-            query = query.filter(Expr::cust_with_values(
-                "title_search @@ to_tsquery($1)",
-                vec![Value::String(Some(Box::new(title.clone())))],
-            ));
+        let similarity = similarity.0.unwrap_or(DEFAULT_SIMILARITY);
+        if !(similarity > 0.0 && similarity <= 1.0) {
+            return Ok(ListPostsResponse::BadRequest(PlainText(
+                "similarity must be greater than 0 and at most 1".to_string(),
+            )));
         }
-        if let Some(author) = &author.0 {
-            query = query.filter(Expr::cust_with_values(
-                "author_search @@ to_tsquery($1)",
-                vec![Value::String(Some(Box::new(author.clone())))],
-            ));
+
+        // The fuzzy search threshold is scoped to this transaction
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let syntax = syntax.0.unwrap_or_default();
+        let parsed_as = language.0.unwrap_or_default();
+        for search in [&title.0, &author.0, &q.0].into_iter().flatten() {
+            if let Some(message) = invalid_query(&tnx, syntax, search, parsed_as).await? {
+                return Ok(ListPostsResponse::BadRequest(PlainText(message)));
+            }
         }
+        let mut query = Posts::find().filter(readable_by(&auth));
         if let Some(creation_time) = &creation_time.0 {
             query = query.filter(entities::posts::Column::CreationTime.gte(creation_time));
         }
         if let Some(status) = &status.0 {
             query = query.filter(entities::posts::Column::PostStatus.eq(status.clone()));
         }
-        let posts: Vec<entities::posts::Model> = query
-            .clone()
-            .order_by(entities::posts::Column::CreationTime, sea_orm::Order::Desc)
-            .limit(limit)
-            .offset(offset)
-            .all(*db)
-            .await
-            .map_err(InternalServerError)?;
-        let mut count = None;
+        if let Some(language) = language.0 {
+            query = query.filter(entities::posts::Column::Language.eq(language.code()));
+        }
+        if let Some(tags) = tags.0.as_deref().filter(|tags| !tags.is_empty()) {
+            let mode = tag_mode.0.unwrap_or(TagMode::Any);
+            let tag_ids = resolve_tag_ids(&tnx, tags).await?;
+            // No post carries a tag that does not exist
+            let tag_ids: Vec<Uuid> = if mode == TagMode::All && tag_ids.contains(&None) {
+                Vec::new()
+            } else {
+                tag_ids.into_iter().flatten().collect()
+            };
+            query = query
+                .filter(entities::posts::Column::Id.in_subquery(tagged_post_ids(&tag_ids, mode)));
+        }
+        if let Some(category) = &category.0 {
+            query = match resolve_tag(&tnx, category).await? {
+                Some(category) => query.filter(in_category(category.id)),
+                None => query.filter(Expr::value(false)),
+            };
+        }
+        if let Some(tags) = exclude_tags.0.as_deref().filter(|tags| !tags.is_empty()) {
+            let tag_ids: Vec<Uuid> = resolve_tag_ids(&tnx, tags)
+                .await?
+                .into_iter()
+                .flatten()
+                .collect();
+            query = query.filter(
+                entities::posts::Column::Id
+                    .not_in_subquery(tagged_post_ids(&tag_ids, TagMode::Any)),
+            );
+        }
+
+        let unsearched = query.clone();
+        if let Some(title) = &title.0 {
+            query = query.filter(syntax.matches("title_search", title, language.0));
+        }
+        if let Some(author) = &author.0 {
+            query = query.filter(syntax.matches("author_search", author, language.0));
+        }
+        if let Some(q) = &q.0 {
+            query = query.filter(syntax.matches("search_vector", q, language.0));
+        }
+
+        // With nothing matching exactly, look for titles and authors with similar words
+        let searching = title.0.is_some() || author.0.is_some() || q.0.is_some();
+        let mut fuzzy_fallback = false;
+        if fuzzy.0.unwrap_or(false) && searching {
+            let exact_match: Option<Uuid> = query
+                .clone()
+                .select_only()
+                .column(entities::posts::Column::Id)
+                .into_tuple()
+                .one(&tnx)
+                .await
+                .map_err(InternalServerError)?;
+            if exact_match.is_none() {
+                set_similarity_threshold(&tnx, similarity).await?;
+                query = unsearched;
+                if let Some(title) = &title.0 {
+                    query = query.filter(resembles("title", title));
+                }
+                if let Some(author) = &author.0 {
+                    query = query.filter(resembles("author", author));
+                }
+                if let Some(q) = &q.0 {
+                    query = query.filter(
+                        Condition::any()
+                            .add(resembles("title", q))
+                            .add(resembles("author", q)),
+                    );
+                }
+                fuzzy_fallback = true;
+            }
+        }
+
+        let order = cursor.as_ref().map_or(sea_orm::Order::Desc, Cursor::order);
+        let mut page = query.clone();
+        if let Some(q) = q.0.as_ref().filter(|_| sort == PostsSort::Relevance) {
+            let rank = if fuzzy_fallback {
+                similarity_rank(q)
+            } else {
+                syntax.rank(q, language.0)
+            };
+            page = page.order_by(rank, sea_orm::Order::Desc);
+        }
+        // One extra row tells whether there is a page after this one
+        page = page
+            .order_by(entities::posts::Column::CreationTime, order.clone())
+            .order_by(entities::posts::Column::Id, order)
+            .limit(limit + 1);
+        page = match &cursor {
+            Some(cursor) => page.filter(cursor.condition()),
+            None => page.offset(offset),
+        };
+
+        let mut response = GetPostsResponse {
+            fuzzy: fuzzy_fallback,
+            ..Default::default()
+        };
+        let post_ids: Vec<Uuid>;
+        match view.0.unwrap_or(PostsView::Slugs) {
+            PostsView::Summary => {
+                let rows = paginate(
+                    summary_rows(&tnx, page).await?,
+                    limit,
+                    cursor.as_ref(),
+                    offset > 0,
+                    |row| (row.creation_time, row.id),
+                );
+                post_ids = rows.items.iter().map(|row| row.id).collect();
+                response.posts = rows.items.iter().map(|row| row.slug.clone()).collect();
+                response.next_cursor = rows.next_cursor;
+                response.prev_cursor = rows.prev_cursor;
+                response.summaries = Some(post_summaries(&tnx, rows.items).await?);
+            }
+            view => {
+                let posts = paginate(
+                    page.all(&tnx).await.map_err(InternalServerError)?,
+                    limit,
+                    cursor.as_ref(),
+                    offset > 0,
+                    |post| (post.creation_time, post.id),
+                );
+                post_ids = posts.items.iter().map(|post| post.id).collect();
+                response.posts = posts.items.iter().map(|post| post.slug.clone()).collect();
+                response.next_cursor = posts.next_cursor;
+                response.prev_cursor = posts.prev_cursor;
+                if view == PostsView::Full {
+                    response.details = Some(posts_with_tags(&tnx, &auth, posts.items).await?);
+                }
+            }
+        }
+        if let Some(q) = &q.0 {
+            response.highlights =
+                Some(highlights(&tnx, syntax, q, language.0, &post_ids, &highlight_options).await?);
+        }
+        // Cursors follow creation order, relevance ranked pages are reached by offset
+        if sort == PostsSort::Relevance {
+            response.next_cursor = None;
+            response.prev_cursor = None;
+        }
         if add_count.0.unwrap_or(false) {
-            let c = query.count(*db).await.map_err(InternalServerError)?;
-            count = Some(c);
+            let c = query
+                .clone()
+                .count(&tnx)
+                .await
+                .map_err(InternalServerError)?;
+            response.count = Some(c);
         }
-        let ids = posts
-            .iter()
-            .map(|post| post.slug.clone())
-            .collect::<Vec<String>>();
+        if with_facets.0.unwrap_or(false) {
+            response.facets = Some(Box::new(facets(&tnx, query).await?));
+        }
+        tnx.commit().await.map_err(InternalServerError)?;
 
-        Ok(Json(GetPostsResponse { posts: ids, count }))
+        let etag = content_etag(&response).map_err(InternalServerError)?;
+        let cache_control = cache_control(&auth);
+        if not_modified(if_none_match.0.as_deref(), None, &etag, None) {
+            return Ok(ListPostsResponse::NotModified(
+                etag,
+                cache_control,
+                VARY.to_string(),
+            ));
+        }
+
+        Ok(ListPostsResponse::Ok(
+            Json(response),
+            etag,
+            cache_control,
+            VARY.to_string(),
+        ))
     }
 
+    /// Read-only despite being a POST, so it takes part in conditional requests
+    /// the same way as the GET endpoints do
     #[oai(method = "post", path = "/bulk_get")]
     async fn bulk_get(
         &self,
+        #[oai(name = "If-None-Match")] if_none_match: Header<Option<String>>,
+        auth: OptionalBearerAuthorization,
         db: Data<&DatabaseConnection>,
         slugs: Json<Vec<String>>,
-    ) -> Result<Json<Vec<PostWithTags>>> {
-        if slugs.0.is_empty() {
-            Ok(Json(vec![]))
-        } else {
-            let posts: Vec<entities::posts::Model> = Posts::find()
+    ) -> Result<BulkGetResponse> {
+        let mut posts = Vec::new();
+
+        if !slugs.0.is_empty() {
+            let found_posts: Vec<entities::posts::Model> = Posts::find()
                 .filter(entities::posts::Column::Slug.is_in(slugs.0.clone()))
+                .filter(readable_by(&auth))
                 .all(*db)
                 .await
                 .map_err(InternalServerError)?;
 
-            let mut posts_with_tags = Vec::new();
+            // Answer in the order the slugs were requested, duplicates only once
+            let mut posts_by_slug: HashMap<String, entities::posts::Model> = found_posts
+                .into_iter()
+                .map(|post| (post.slug.clone(), post))
+                .collect();
+            let ordered_posts = slugs
+                .0
+                .iter()
+                .filter_map(|slug| posts_by_slug.remove(slug))
+                .collect();
 
-            for post in posts {
-                // Fetch tags for each post
-                let tags: Vec<entities::tags::Model> = post
-                    .find_related(entities::tags::Entity)
-                    .all(*db)
-                    .await
-                    .map_err(InternalServerError)?;
-
-                let tag_names: Vec<String> = tags.into_iter().map(|tag| tag.name).collect();
-
-                let post_with_tags = PostWithTags {
-                    id: post.id,
-                    slug: post.slug,
-                    title: post.title,
-                    hero_image: post.hero_image,
-                    creation_time: post.creation_time,
-                    body: post.body,
-                    author: post.author,
-                    created_by: post.created_by,
-                    subheading: post.subheading,
-                    last_edit: post.last_edit,
-                    post_status: post.post_status,
-                    tags: tag_names,
-                };
-
-                posts_with_tags.push(post_with_tags);
-            }
+            posts = posts_with_tags(*db, &auth, ordered_posts).await?;
+        }
 
-            Ok(Json(posts_with_tags))
+        let etag = content_etag(&posts).map_err(InternalServerError)?;
+        let cache_control = cache_control(&auth);
+        if not_modified(if_none_match.0.as_deref(), None, &etag, None) {
+            return Ok(BulkGetResponse::NotModified(
+                etag,
+                cache_control,
+                VARY.to_string(),
+            ));
         }
+
+        Ok(BulkGetResponse::Ok(
+            Json(posts),
+            etag,
+            cache_control,
+            VARY.to_string(),
+        ))
     }
 
     #[oai(path = "/", method = "post")]
@@ -300,6 +1354,12 @@ impl PostsApi {
             )));
         }
 
+        if !valid_schedule(request.publish_at, request.unpublish_at) {
+            return Ok(InsertPostResponse::BadRequest(PlainText(
+                "publish_at must be before unpublish_at".to_string(),
+            )));
+        }
+
         let user_id = &claims.sub;
 
         let user =
@@ -311,31 +1371,47 @@ impl PostsApi {
                     Error::from_string("User not found", poem::http::StatusCode::UNAUTHORIZED)
                 })?;
 
-        let post_exists = Posts::find()
-            .filter(
-                entities::posts::Column::Slug.eq(request.title.to_lowercase().replace(" ", "_")),
-            )
-            .one(*db)
-            .await
-            .map_err(InternalServerError)?;
-
-        if post_exists.is_some() {
-            return Ok(InsertPostResponse::Conflict);
-        }
+        let post_id = Uuid::new_v4();
+        let slug = match &request.slug {
+            Some(slug) => {
+                if let Err(err) = slug::validate(slug) {
+                    return Ok(InsertPostResponse::BadRequest(PlainText(err.to_string())));
+                }
+                if slug_taken(*db, slug, post_id).await? {
+                    return Ok(InsertPostResponse::Conflict);
+                }
+                slug.clone()
+            }
+            None => unique_slug(*db, &slug::from_title(&request.title), post_id).await?,
+        };
 
         let tags = request.tags.clone().unwrap_or_default();
 
         let tnx = db.begin().await.map_err(InternalServerError)?;
 
-        let mut tag_ids = Vec::new();
-        for tag in tags {
-            let tag = create_tag_if_not_exists(&tnx, &tag).await?;
-            tag_ids.push(tag.id);
-        }
+        let language = request.language.unwrap_or_default();
+        let translation_group_id = match &request.translation_of {
+            Some(original) => {
+                let Some(group_id) = translation_group(&tnx, original).await? else {
+                    return Ok(InsertPostResponse::BadRequest(PlainText(format!(
+                        "No post to translate with slug {original}"
+                    ))));
+                };
+                let translations = other_translations(&tnx, group_id, post_id).await?;
+                if translations
+                    .iter()
+                    .any(|translation| translation.language == language.code())
+                {
+                    return Ok(InsertPostResponse::Conflict);
+                }
+                group_id
+            }
+            None => post_id,
+        };
 
         let new_post = entities::posts::ActiveModel {
-            id: Set(Uuid::new_v4()),
-            slug: Set(request.title.to_lowercase().replace(" ", "_")),
+            id: Set(post_id),
+            slug: Set(slug),
             title: Set(request.title.clone()),
             body: Set(request.body.clone()),
             created_by: Set(user.id),
@@ -344,18 +1420,28 @@ impl PostsApi {
             creation_time: Set(chrono::Utc::now().naive_utc()),
             last_edit: Set(None),
             hero_image: Set(request.hero_image.clone()),
+            publish_at: Set(request.publish_at),
+            unpublish_at: Set(request.unpublish_at),
+            deleted_at: Set(None),
+            language: Set(language.code().to_string()),
+            translation_group_id: Set(translation_group_id),
             ..Default::default()
         };
 
-        let post = new_post.insert(&tnx).await.map_err(InternalServerError)?;
+        // A concurrent insert may have taken the slug or the language in the meantime
+        let post = match new_post.insert(&tnx).await {
+            Ok(post) => post,
+            Err(err) if is_unique_violation(&err) => return Ok(InsertPostResponse::Conflict),
+            Err(err) => return Err(InternalServerError(err)),
+        };
+        touch_translations(&tnx, post.translation_group_id, post.id)
+            .await
+            .map_err(InternalServerError)?;
 
-        for tag_id in tag_ids {
-            let post_tag = entities::post_tags::ActiveModel {
-                post_id: Set(post.id),
-                tag_id: Set(tag_id),
-            };
-            post_tag.insert(&tnx).await.map_err(InternalServerError)?;
-        }
+        let tag_names = set_post_tags(&tnx, post.id, &tags).await?;
+        record_revision(&tnx, &post, tag_names, Some(user.id))
+            .await
+            .map_err(InternalServerError)?;
 
         tnx.commit().await.map_err(InternalServerError)?;
 
@@ -365,10 +1451,12 @@ impl PostsApi {
         ))))
     }
 
+    /// Moves a post to the trash, from where it can be restored until it is purged
     #[oai(method = "delete", path = "/:post_slug")]
     async fn delete_post(
         &self,
         post_slug: Path<String>,
+        #[oai(name = "If-Match")] if_match_header: Header<Option<String>>,
         claims: BearerAuthorization,
         db: Data<&DatabaseConnection>,
     ) -> Result<DeletePostResponse> {
@@ -378,9 +1466,14 @@ impl PostsApi {
                 poem::http::StatusCode::FORBIDDEN,
             ));
         }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
         let post = Posts::find()
             .filter(entities::posts::Column::Slug.eq(&post_slug.0))
-            .one(*db)
+            .filter(entities::posts::Column::DeletedAt.is_null())
+            .lock_exclusive()
+            .one(&tnx)
             .await
             .map_err(InternalServerError)?;
 
@@ -389,9 +1482,133 @@ impl PostsApi {
             None => return Ok(DeletePostResponse::NotFound),
         };
 
-        post.delete(*db).await.map_err(InternalServerError)?;
+        if !if_match(if_match_header.0.as_deref(), &post_etag(&post)) {
+            return Ok(DeletePostResponse::PreconditionFailed);
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        let mut trashed: entities::posts::ActiveModel = post.clone().into();
+        trashed.deleted_at = Set(Some(now));
+        trashed.last_edit = Set(Some(now));
+        let trashed = trashed.update(&tnx).await.map_err(InternalServerError)?;
+        touch_changed_translations(&tnx, &post, &trashed)
+            .await
+            .map_err(InternalServerError)?;
+        tnx.commit().await.map_err(InternalServerError)?;
+
         Ok(DeletePostResponse::Ok(PlainText(format!(
-            "Post {} deleted",
+            "Post {} moved to trash",
+            post_slug.0
+        ))))
+    }
+
+    /// Posts in the trash, most recently deleted first
+    #[oai(method = "get", path = "/trash")]
+    async fn list_trash(
+        &self,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<ListTrashResponse> {
+        if !claims.has_permission("delete", "post") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let posts = Posts::find()
+            .filter(entities::posts::Column::DeletedAt.is_not_null())
+            .order_by_desc(entities::posts::Column::DeletedAt)
+            .all(*db)
+            .await
+            .map_err(InternalServerError)?;
+
+        let trashed = posts
+            .into_iter()
+            .filter_map(|post| {
+                Some(TrashedPost {
+                    deleted_at: post.deleted_at?,
+                    slug: post.slug,
+                    title: post.title,
+                    author: post.author,
+                    post_status: post.post_status,
+                })
+            })
+            .collect();
+
+        Ok(ListTrashResponse::Ok(Json(trashed)))
+    }
+
+    /// Takes a post out of the trash, with the status it had when it was deleted
+    #[oai(method = "post", path = "/:post_slug/restore")]
+    async fn restore_post(
+        &self,
+        post_slug: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<TrashActionResponse> {
+        if !claims.has_permission("delete", "post") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let Some(post) = find_trashed_post(&tnx, &post_slug.0).await? else {
+            return Ok(TrashActionResponse::NotFound);
+        };
+
+        // Its language may have been taken by another translation in the meantime
+        let translations = other_translations(&tnx, post.translation_group_id, post.id).await?;
+        let language_taken = translations
+            .iter()
+            .any(|translation| translation.language == post.language);
+
+        let mut restored: entities::posts::ActiveModel = post.clone().into();
+        if language_taken {
+            restored.translation_group_id = Set(Uuid::new_v4());
+        }
+        restored.deleted_at = Set(None);
+        restored.last_edit = Set(Some(chrono::Utc::now().naive_utc()));
+        let restored = restored.update(&tnx).await.map_err(InternalServerError)?;
+        touch_changed_translations(&tnx, &post, &restored)
+            .await
+            .map_err(InternalServerError)?;
+        let post = restored;
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(TrashActionResponse::Ok(PlainText(post.slug)))
+    }
+
+    /// Permanently deletes a post that is in the trash
+    #[oai(method = "delete", path = "/:post_slug/purge")]
+    async fn purge_post(
+        &self,
+        post_slug: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<TrashActionResponse> {
+        if !claims.has_permission("delete", "post") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let Some(post) = find_trashed_post(&tnx, &post_slug.0).await? else {
+            return Ok(TrashActionResponse::NotFound);
+        };
+
+        // Tags, revisions and retired slugs of the post are removed by their foreign keys
+        post.delete(&tnx).await.map_err(InternalServerError)?;
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(TrashActionResponse::Ok(PlainText(format!(
+            "Post {} purged",
             post_slug.0
         ))))
     }
@@ -400,6 +1617,7 @@ impl PostsApi {
     async fn patch_post(
         &self,
         post_slug: Path<String>,
+        #[oai(name = "If-Match")] if_match_header: Header<Option<String>>,
         claims: BearerAuthorization,
         db: Data<&DatabaseConnection>,
         request: Json<PatchPostRequest>,
@@ -411,18 +1629,26 @@ impl PostsApi {
             ));
         }
 
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
         let post_model = Posts::find()
             .filter(entities::posts::Column::Slug.eq(post_slug.0.clone()))
-            .one(*db)
+            .filter(entities::posts::Column::DeletedAt.is_null())
+            .lock_exclusive()
+            .one(&tnx)
             .await
             .map_err(InternalServerError)?;
 
-        let mut post: entities::posts::ActiveModel = match post_model {
-            Some(model) => model.into(),
+        let post_model = match post_model {
+            Some(model) => model,
             None => return Ok(PatchPostResponse::NotFound),
         };
 
-        let tnx = db.begin().await.map_err(InternalServerError)?;
+        if !if_match(if_match_header.0.as_deref(), &post_etag(&post_model)) {
+            return Ok(PatchPostResponse::PreconditionFailed);
+        }
+
+        let mut post: entities::posts::ActiveModel = post_model.clone().into();
 
         if let Some(title) = &request.title {
             if title.is_empty() {
@@ -430,75 +1656,135 @@ impl PostsApi {
                     "Title cannot be empty".to_string(),
                 )));
             }
-            post.title = Set(title.clone());
-            let slug = title.to_lowercase().replace(" ", "_");
+            post.title.set_if_not_equals(title.clone());
+        }
 
-            let slug_being_used = Posts::find()
-                .filter(entities::posts::Column::Slug.eq(slug.clone()))
-                .one(&tnx)
-                .await
-                .map_err(InternalServerError)?;
-            if let Some(existing_post) = slug_being_used {
-                let current_id = post.id.as_ref();
-                if existing_post.id != *current_id {
+        let slug = match (&request.slug, &request.title) {
+            (Some(slug), _) => {
+                if let Err(err) = slug::validate(slug) {
+                    return Ok(PatchPostResponse::BadRequest(PlainText(err.to_string())));
+                }
+                if slug_taken(&tnx, slug, post_model.id).await? {
                     return Ok(PatchPostResponse::Conflict);
                 }
+                Some(slug.clone())
             }
-
-            post.slug = Set(slug);
+            // A resubmitted title keeps the current slug, even a custom one
+            (None, Some(title)) if *title != post_model.title => {
+                Some(unique_slug(&tnx, &slug::from_title(title), post_model.id).await?)
+            }
+            (None, _) => None,
+        };
+        if let Some(slug) = slug {
+            retire_slug(&tnx, post_model.id, &post_model.slug, &slug).await?;
+            post.slug.set_if_not_equals(slug);
         }
         if let Some(author) = &request.author {
-            post.author = Set(author.clone());
+            post.author.set_if_not_equals(author.clone());
         }
         if let Some(body) = &request.body {
-            post.body = Set(body.clone());
+            post.body.set_if_not_equals(body.clone());
         }
         if let Some(subheading) = &request.subheading {
-            post.subheading = Set(subheading.clone());
+            post.subheading.set_if_not_equals(subheading.clone());
         }
         if let Some(status) = &request.status {
-            post.post_status = Set(status.clone());
+            post.post_status.set_if_not_equals(status.clone());
         }
         if let Some(hero_image) = &request.hero_image {
-            post.hero_image = Set(Some(hero_image.clone()));
+            post.hero_image.set_if_not_equals(Some(hero_image.clone()));
         }
-
-        if let Some(tags) = &request.tags {
-            let current_id = post.id.as_ref();
-            entities::post_tags::Entity::delete_many()
-                .filter(entities::post_tags::Column::PostId.eq(current_id.clone()))
-                .exec(&tnx)
-                .await
-                .map_err(InternalServerError)?;
-
-            let mut tag_ids = Vec::new();
-            for tag in tags {
-                let tag = create_tag_if_not_exists(&tnx, tag).await?;
-                tag_ids.push(tag.id);
+        let language = request
+            .language
+            .map_or(post_model.language.clone(), |language| {
+                language.code().to_string()
+            });
+        let translation_group_id = match &request.translation_of {
+            MaybeUndefined::Value(original) => match translation_group(&tnx, original).await? {
+                Some(group_id) => group_id,
+                None => {
+                    return Ok(PatchPostResponse::BadRequest(PlainText(format!(
+                        "No post to translate with slug {original}"
+                    ))));
+                }
+            },
+            MaybeUndefined::Null => {
+                let translations =
+                    other_translations(&tnx, post_model.translation_group_id, post_model.id)
+                        .await?;
+                if translations.is_empty() {
+                    post_model.translation_group_id
+                } else {
+                    Uuid::new_v4()
+                }
             }
-
-            for tag_id in tag_ids {
-                let post_tag = entities::post_tags::ActiveModel {
-                    post_id: post.id.clone(),
-                    tag_id: Set(tag_id),
-                };
-                post_tag.insert(&tnx).await.map_err(InternalServerError)?;
+            MaybeUndefined::Undefined => post_model.translation_group_id,
+        };
+        if language != post_model.language
+            || translation_group_id != post_model.translation_group_id
+        {
+            let translations =
+                other_translations(&tnx, translation_group_id, post_model.id).await?;
+            if translations
+                .iter()
+                .any(|translation| translation.language == language)
+            {
+                return Ok(PatchPostResponse::Conflict);
             }
+            post.language.set_if_not_equals(language);
+            post.translation_group_id
+                .set_if_not_equals(translation_group_id);
         }
-        #[allow(unused)]
-        let mut slug = post_slug.0;
-        if post.is_changed() {
-            post.last_edit = Set(Some(chrono::Utc::now().naive_utc()));
-            let model = post.update(&tnx).await.map_err(InternalServerError)?;
-            slug = model.slug;
-        } else {
-            return Ok(PatchPostResponse::Ok(PlainText(
-                "No changes made".to_string(),
+        let mut publish_at = post_model.publish_at;
+        let mut unpublish_at = post_model.unpublish_at;
+        request.publish_at.update_to(&mut publish_at);
+        request.unpublish_at.update_to(&mut unpublish_at);
+        if !valid_schedule(publish_at, unpublish_at) {
+            return Ok(PatchPostResponse::BadRequest(PlainText(
+                "publish_at must be before unpublish_at".to_string(),
             )));
         }
+        if publish_at != post_model.publish_at {
+            post.publish_at = Set(publish_at);
+        }
+        if unpublish_at != post_model.unpublish_at {
+            post.unpublish_at = Set(unpublish_at);
+        }
+
+        let current_tags = post_tag_names(&tnx, &post_model).await?;
+        let mut tag_names = current_tags.clone();
+        if let Some(tags) = &request.tags
+            && !same_tags(tags, &current_tags)
+        {
+            tag_names = set_post_tags(&tnx, post_model.id, tags).await?;
+        }
+
+        if !post.is_changed() && tag_names == current_tags {
+            return Ok(PatchPostResponse::Ok(
+                PlainText("No changes made".to_string()),
+                post_etag(&post_model),
+            ));
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        stamp_publication(&mut post, now);
+        post.last_edit = Set(Some(now));
+        let model = match post.update(&tnx).await {
+            Ok(model) => model,
+            Err(err) if is_unique_violation(&err) => return Ok(PatchPostResponse::Conflict),
+            Err(err) => return Err(InternalServerError(err)),
+        };
+        touch_changed_translations(&tnx, &post_model, &model)
+            .await
+            .map_err(InternalServerError)?;
+        let editor = Uuid::from_str(&claims.sub).ok();
+        record_revision(&tnx, &model, tag_names, editor)
+            .await
+            .map_err(InternalServerError)?;
 
         tnx.commit().await.map_err(InternalServerError)?;
 
-        Ok(PatchPostResponse::Ok(PlainText(format!("{}", slug))))
+        let etag = post_etag(&model);
+        Ok(PatchPostResponse::Ok(PlainText(model.slug), etag))
     }
 }
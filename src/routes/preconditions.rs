@@ -0,0 +1,234 @@
+//! Entity tags and conditional request headers (RFC 9110, section 13)
+
+use sha2::{Digest, Sha256};
+
+use crate::auth::OptionalBearerAuthorization;
+use crate::config::CONFIG;
+
+/// Read responses depend on who is asking, see `readable_by`
+pub(crate) const VARY: &str = "Authorization";
+
+/// A single post is also served in the language asked for with `Accept-Language`
+pub(crate) const VARY_LANGUAGE: &str = "Authorization, Accept-Language";
+
+/// Strong entity tag of a post, it changes every time the post is edited
+pub(crate) fn post_etag(post: &entities::posts::Model) -> String {
+    let modified = post.last_edit.unwrap_or(post.creation_time);
+    format!(
+        "\"{}-{:x}\"",
+        post.id.simple(),
+        modified.and_utc().timestamp_micros()
+    )
+}
+
+/// Evaluates an `If-Match` header against the current entity tag of a resource.
+/// A missing header always matches, weak tags never do.
+pub(crate) fn if_match(header: Option<&str>, etag: &str) -> bool {
+    let Some(header) = header else {
+        return true;
+    };
+    let header = header.trim();
+    header == "*"
+        || header
+            .split(',')
+            .map(str::trim)
+            .any(|candidate| !candidate.starts_with("W/") && candidate == etag)
+}
+
+/// Strong entity tag derived from the serialized body of a response, for responses
+/// made of several posts
+pub(crate) fn content_etag<T: serde::Serialize>(content: &T) -> Result<String, serde_json::Error> {
+    let digest = Sha256::digest(serde_json::to_vec(content)?);
+    let hex: String = digest[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    Ok(format!("\"{hex}\""))
+}
+
+pub(crate) fn post_last_modified(post: &entities::posts::Model) -> chrono::NaiveDateTime {
+    post.last_edit.unwrap_or(post.creation_time)
+}
+
+/// Formats a UTC instant as an HTTP-date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+pub(crate) fn http_date(instant: chrono::NaiveDateTime) -> String {
+    instant
+        .and_utc()
+        .format("%a, %d %b %Y %H:%M:%S GMT")
+        .to_string()
+}
+
+/// `Cache-Control` of read responses. Responses that depend on a bearer token
+/// must not end up in shared caches.
+pub(crate) fn cache_control(auth: &OptionalBearerAuthorization) -> String {
+    match auth.claims() {
+        Some(_) => "private, no-cache".to_string(),
+        None => CONFIG.cache_control.clone(),
+    }
+}
+
+/// Evaluates `If-None-Match` and, when it is absent, `If-Modified-Since`.
+/// Returns true when the client copy is still current and a 304 can be sent instead.
+pub(crate) fn not_modified(
+    if_none_match: Option<&str>,
+    if_modified_since: Option<&str>,
+    etag: &str,
+    last_modified: Option<chrono::NaiveDateTime>,
+) -> bool {
+    if let Some(header) = if_none_match {
+        let header = header.trim();
+        return header == "*"
+            || header
+                .split(',')
+                .map(str::trim)
+                .any(|candidate| weak_eq(candidate, etag));
+    }
+
+    let since = if_modified_since
+        .and_then(|header| chrono::DateTime::parse_from_rfc2822(header.trim()).ok());
+    match (since, last_modified) {
+        // HTTP-dates only have second precision
+        (Some(since), Some(last_modified)) => {
+            last_modified.and_utc().timestamp() <= since.timestamp()
+        }
+        _ => false,
+    }
+}
+
+fn weak_eq(a: &str, b: &str) -> bool {
+    a.trim_start_matches("W/") == b.trim_start_matches("W/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(last_edit: Option<chrono::NaiveDateTime>) -> entities::posts::Model {
+        entities::posts::Model {
+            id: uuid::Uuid::from_u128(0xabc),
+            slug: "post".to_string(),
+            title: "Post".to_string(),
+            hero_image: None,
+            creation_time: chrono::DateTime::from_timestamp_micros(1_000_000)
+                .unwrap()
+                .naive_utc(),
+            body: String::new(),
+            author: String::new(),
+            created_by: uuid::Uuid::nil(),
+            subheading: String::new(),
+            last_edit,
+            post_status: entities::sea_orm_active_enums::PostsStatusEnum::Draft,
+            publish_at: None,
+            unpublish_at: None,
+            deleted_at: None,
+            language: "en".to_string(),
+            title_search: String::new(),
+            author_search: String::new(),
+            search_vector: String::new(),
+            translation_group_id: uuid::Uuid::nil(),
+            published_at: None,
+        }
+    }
+
+    #[test]
+    fn post_etag_follows_last_edit() {
+        let created = post(None);
+        assert_eq!(
+            post_etag(&created),
+            "\"00000000000000000000000000000abc-f4240\""
+        );
+
+        let edited = post(Some(
+            chrono::DateTime::from_timestamp_micros(1_000_001)
+                .unwrap()
+                .naive_utc(),
+        ));
+        assert_eq!(
+            post_etag(&edited),
+            "\"00000000000000000000000000000abc-f4241\""
+        );
+    }
+
+    #[test]
+    fn if_match_without_header_matches() {
+        assert!(if_match(None, "\"a\""));
+    }
+
+    #[test]
+    fn if_match_any() {
+        assert!(if_match(Some(" * "), "\"a\""));
+    }
+
+    #[test]
+    fn if_match_uses_strong_comparison() {
+        assert!(if_match(Some("\"a\""), "\"a\""));
+        assert!(if_match(Some("\"b\", \"a\""), "\"a\""));
+        assert!(!if_match(Some("\"b\""), "\"a\""));
+        assert!(!if_match(Some("W/\"a\""), "\"a\""));
+    }
+
+    fn at(seconds: i64, micros: u32) -> chrono::NaiveDateTime {
+        chrono::DateTime::from_timestamp(seconds, micros * 1000)
+            .unwrap()
+            .naive_utc()
+    }
+
+    #[test]
+    fn http_date_format() {
+        assert_eq!(http_date(at(784111777, 0)), "Sun, 06 Nov 1994 08:49:37 GMT");
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        assert!(not_modified(Some("W/\"a\""), None, "\"a\"", None));
+        assert!(not_modified(Some("\"b\", \"a\""), None, "W/\"a\"", None));
+        assert!(!not_modified(Some("W/\"b\""), None, "\"a\"", None));
+    }
+
+    #[test]
+    fn if_none_match_any() {
+        assert!(not_modified(Some("*"), None, "\"a\"", None));
+    }
+
+    #[test]
+    fn if_none_match_takes_precedence() {
+        let since = "Sun, 06 Nov 1994 08:49:37 GMT";
+        let last_modified = Some(at(784111777, 0));
+        assert!(!not_modified(
+            Some("\"b\""),
+            Some(since),
+            "\"a\"",
+            last_modified
+        ));
+    }
+
+    #[test]
+    fn if_modified_since_has_second_precision() {
+        let since = Some("Sun, 06 Nov 1994 08:49:37 GMT");
+        assert!(not_modified(
+            None,
+            since,
+            "\"a\"",
+            Some(at(784111777, 999_999))
+        ));
+        assert!(not_modified(None, since, "\"a\"", Some(at(784111776, 0))));
+        assert!(!not_modified(None, since, "\"a\"", Some(at(784111778, 0))));
+    }
+
+    #[test]
+    fn without_validators_is_modified() {
+        assert!(!not_modified(None, None, "\"a\"", Some(at(0, 0))));
+        assert!(!not_modified(
+            None,
+            Some("yesterday"),
+            "\"a\"",
+            Some(at(0, 0))
+        ));
+        assert!(!not_modified(
+            None,
+            Some("Sun, 06 Nov 1994 08:49:37 GMT"),
+            "\"a\"",
+            None
+        ));
+    }
+}
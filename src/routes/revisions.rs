@@ -0,0 +1,353 @@
+use std::str::FromStr;
+
+use crate::auth::BearerAuthorization;
+use crate::routes::ApiTags;
+use crate::routes::posts::{
+    post_tag_names, retire_slug, set_post_tags, touch_changed_translations, unique_slug,
+};
+use crate::slug;
+
+use entities::post_revisions::Entity as PostRevisions;
+use entities::posts::Entity as Posts;
+use entities::sea_orm_active_enums::PostsStatusEnum;
+use poem::Error;
+use poem::{Result, error::InternalServerError, web::Data};
+use poem_openapi::param::Query;
+use poem_openapi::payload::PlainText;
+use poem_openapi::{ApiResponse, OpenApi, param::Path, payload::Json};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use similar::TextDiff;
+use uuid::Uuid;
+
+pub struct RevisionsApi;
+
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct RevisionSummary {
+    pub revision_number: i32,
+    pub title: String,
+    pub post_status: PostsStatusEnum,
+    pub edited_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct Revision {
+    pub revision_number: i32,
+    pub title: String,
+    pub subheading: String,
+    pub body: String,
+    pub tags: Vec<String>,
+    pub post_status: PostsStatusEnum,
+    pub edited_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<entities::post_revisions::Model> for Revision {
+    fn from(revision: entities::post_revisions::Model) -> Self {
+        Self {
+            revision_number: revision.revision_number,
+            tags: revision_tags(&revision),
+            title: revision.title,
+            subheading: revision.subheading,
+            body: revision.body,
+            post_status: revision.post_status,
+            edited_by: revision.edited_by,
+            created_at: revision.created_at,
+        }
+    }
+}
+
+#[derive(ApiResponse)]
+enum ListRevisionsResponse {
+    #[oai(status = 200)]
+    Ok(Json<Vec<RevisionSummary>>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+#[derive(ApiResponse)]
+enum GetRevisionResponse {
+    #[oai(status = 200)]
+    Ok(Json<Revision>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+#[derive(ApiResponse)]
+enum RevisionDiffResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+#[derive(ApiResponse)]
+enum RestoreRevisionResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+fn revision_tags(revision: &entities::post_revisions::Model) -> Vec<String> {
+    serde_json::from_value(revision.tags.clone()).unwrap_or_default()
+}
+
+/// Snapshots the current state of a post as its next revision
+pub(crate) async fn record_revision<T: ConnectionTrait>(
+    db: &T,
+    post: &entities::posts::Model,
+    mut tags: Vec<String>,
+    edited_by: Option<Uuid>,
+) -> Result<entities::post_revisions::Model, DbErr> {
+    let last_revision: Option<Option<i32>> = PostRevisions::find()
+        .select_only()
+        .column_as(
+            entities::post_revisions::Column::RevisionNumber.max(),
+            "max",
+        )
+        .filter(entities::post_revisions::Column::PostId.eq(post.id))
+        .into_tuple()
+        .one(db)
+        .await?;
+
+    tags.sort();
+    let revision = entities::post_revisions::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        post_id: Set(post.id),
+        revision_number: Set(last_revision.flatten().unwrap_or(0) + 1),
+        title: Set(post.title.clone()),
+        subheading: Set(post.subheading.clone()),
+        body: Set(post.body.clone()),
+        tags: Set(serde_json::Value::from(tags)),
+        post_status: Set(post.post_status.clone()),
+        edited_by: Set(edited_by),
+        created_at: Set(chrono::Utc::now().naive_utc()),
+    };
+    revision.insert(db).await
+}
+
+/// Plain text form of a revision, used as the input of the unified diff
+fn render_revision(revision: &entities::post_revisions::Model) -> String {
+    format!(
+        "Title: {}\nSubheading: {}\nStatus: {:?}\nTags: {}\n\n{}\n",
+        revision.title,
+        revision.subheading,
+        revision.post_status,
+        revision_tags(revision).join(", "),
+        revision.body
+    )
+}
+
+async fn find_post<T: ConnectionTrait>(
+    db: &T,
+    post_slug: &str,
+) -> Result<Option<entities::posts::Model>> {
+    Posts::find()
+        .filter(entities::posts::Column::Slug.eq(post_slug))
+        .filter(entities::posts::Column::DeletedAt.is_null())
+        .one(db)
+        .await
+        .map_err(InternalServerError)
+}
+
+async fn find_revision<T: ConnectionTrait>(
+    db: &T,
+    post_id: Uuid,
+    revision_number: i32,
+) -> Result<Option<entities::post_revisions::Model>> {
+    PostRevisions::find()
+        .filter(entities::post_revisions::Column::PostId.eq(post_id))
+        .filter(entities::post_revisions::Column::RevisionNumber.eq(revision_number))
+        .one(db)
+        .await
+        .map_err(InternalServerError)
+}
+
+#[OpenApi(prefix_path = "/posts", tag = "ApiTags::Revisions")]
+impl RevisionsApi {
+    #[oai(method = "get", path = "/:post_slug/revisions")]
+    async fn list_revisions(
+        &self,
+        post_slug: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<ListRevisionsResponse> {
+        if !claims.has_permission("update", "post") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let Some(post) = find_post(*db, &post_slug.0).await? else {
+            return Ok(ListRevisionsResponse::NotFound);
+        };
+
+        let revisions = PostRevisions::find()
+            .filter(entities::post_revisions::Column::PostId.eq(post.id))
+            .order_by_desc(entities::post_revisions::Column::RevisionNumber)
+            .all(*db)
+            .await
+            .map_err(InternalServerError)?;
+
+        let summaries = revisions
+            .into_iter()
+            .map(|revision| RevisionSummary {
+                revision_number: revision.revision_number,
+                title: revision.title,
+                post_status: revision.post_status,
+                edited_by: revision.edited_by,
+                created_at: revision.created_at,
+            })
+            .collect();
+
+        Ok(ListRevisionsResponse::Ok(Json(summaries)))
+    }
+
+    /// Unified diff between two revisions, `to` defaults to the latest revision
+    #[oai(method = "get", path = "/:post_slug/revisions/diff")]
+    async fn diff_revisions(
+        &self,
+        post_slug: Path<String>,
+        from: Query<i32>,
+        to: Query<Option<i32>>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<RevisionDiffResponse> {
+        if !claims.has_permission("update", "post") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let Some(post) = find_post(*db, &post_slug.0).await? else {
+            return Ok(RevisionDiffResponse::NotFound);
+        };
+
+        let Some(old) = find_revision(*db, post.id, from.0).await? else {
+            return Ok(RevisionDiffResponse::NotFound);
+        };
+        let new = match to.0 {
+            Some(to) => find_revision(*db, post.id, to).await?,
+            None => PostRevisions::find()
+                .filter(entities::post_revisions::Column::PostId.eq(post.id))
+                .order_by_desc(entities::post_revisions::Column::RevisionNumber)
+                .one(*db)
+                .await
+                .map_err(InternalServerError)?,
+        };
+        let Some(new) = new else {
+            return Ok(RevisionDiffResponse::NotFound);
+        };
+
+        let old_text = render_revision(&old);
+        let new_text = render_revision(&new);
+        let diff = TextDiff::from_lines(&old_text, &new_text)
+            .unified_diff()
+            .header(
+                &format!("{}@{}", post.slug, old.revision_number),
+                &format!("{}@{}", post.slug, new.revision_number),
+            )
+            .to_string();
+
+        Ok(RevisionDiffResponse::Ok(PlainText(diff)))
+    }
+
+    #[oai(method = "get", path = "/:post_slug/revisions/:revision_number")]
+    async fn get_revision(
+        &self,
+        post_slug: Path<String>,
+        revision_number: Path<i32>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<GetRevisionResponse> {
+        if !claims.has_permission("update", "post") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let Some(post) = find_post(*db, &post_slug.0).await? else {
+            return Ok(GetRevisionResponse::NotFound);
+        };
+
+        match find_revision(*db, post.id, revision_number.0).await? {
+            Some(revision) => Ok(GetRevisionResponse::Ok(Json(revision.into()))),
+            None => Ok(GetRevisionResponse::NotFound),
+        }
+    }
+
+    /// Makes the title, subheading, body and tags of an old revision the current version
+    /// of the post. The post keeps its current status, a restore never publishes or
+    /// unpublishes it. The restore itself is recorded as a new revision.
+    #[oai(
+        method = "post",
+        path = "/:post_slug/revisions/:revision_number/restore"
+    )]
+    async fn restore_revision(
+        &self,
+        post_slug: Path<String>,
+        revision_number: Path<i32>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<RestoreRevisionResponse> {
+        if !claims.has_permission("update", "post") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let post_model = Posts::find()
+            .filter(entities::posts::Column::Slug.eq(&post_slug.0))
+            .filter(entities::posts::Column::DeletedAt.is_null())
+            .lock_exclusive()
+            .one(&tnx)
+            .await
+            .map_err(InternalServerError)?;
+        let Some(post_model) = post_model else {
+            return Ok(RestoreRevisionResponse::NotFound);
+        };
+        let Some(revision) = find_revision(&tnx, post_model.id, revision_number.0).await? else {
+            return Ok(RestoreRevisionResponse::NotFound);
+        };
+
+        let mut post: entities::posts::ActiveModel = post_model.clone().into();
+        if revision.title != post_model.title {
+            let slug = unique_slug(&tnx, &slug::from_title(&revision.title), post_model.id).await?;
+            retire_slug(&tnx, post_model.id, &post_model.slug, &slug).await?;
+            post.slug = Set(slug);
+        }
+        post.title = Set(revision.title.clone());
+        post.subheading = Set(revision.subheading.clone());
+        post.body = Set(revision.body.clone());
+        post.last_edit = Set(Some(chrono::Utc::now().naive_utc()));
+
+        let mut tag_names = post_tag_names(&tnx, &post_model).await?;
+        let revision_tags = revision_tags(&revision);
+        if tag_names != revision_tags {
+            tag_names = set_post_tags(&tnx, post_model.id, &revision_tags).await?;
+        }
+
+        let model = post.update(&tnx).await.map_err(InternalServerError)?;
+        touch_changed_translations(&tnx, &post_model, &model)
+            .await
+            .map_err(InternalServerError)?;
+        let editor = Uuid::from_str(&claims.sub).ok();
+        record_revision(&tnx, &model, tag_names, editor)
+            .await
+            .map_err(InternalServerError)?;
+
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(RestoreRevisionResponse::Ok(PlainText(model.slug)))
+    }
+}
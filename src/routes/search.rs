@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::language::Language;
+
+use entities::posts::Entity as Posts;
+use entities::sea_orm_active_enums::PostsStatusEnum;
+use migration::{Expr, SimpleExpr};
+use poem::{Result, error::InternalServerError};
+use poem_openapi::types::ToJSON;
+use sea_orm::{
+    ActiveEnum, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter,
+    QuerySelect, QueryTrait, RuntimeErr, Select, Statement, sqlx,
+};
+use uuid::Uuid;
+
+const DEFAULT_START: &str = "<mark>";
+const DEFAULT_STOP: &str = "</mark>";
+const DEFAULT_MAX_FRAGMENTS: u32 = 3;
+const MAX_MARKER_LENGTH: usize = 32;
+const MAX_FRAGMENTS: u32 = 10;
+
+/// How a search query typed by a reader is turned into a tsquery
+#[derive(poem_openapi::Enum, Clone, Copy, PartialEq, Eq, Default)]
+#[oai(rename_all = "lowercase")]
+pub enum QuerySyntax {
+    /// Search engine style: quoted phrases, `or` and `-word`, never fails to parse
+    #[default]
+    Websearch,
+    /// Every word must match, operators are ignored
+    Plain,
+    /// Raw tsquery syntax with `&`, `|`, `!` and `<->`
+    Raw,
+}
+
+/// Text search configuration posts written in `language` are stemmed with
+fn search_config(language: Language) -> String {
+    format!("post_search_config('{}')", language.code())
+}
+
+/// SQL of `expr` for the posts in `language`, or when no language is given, one
+/// branch per language picked by the language of each post. Every branch only uses
+/// constant configurations, a configuration read from the row can't use the GIN index.
+fn per_language(language: Option<Language>, expr: impl Fn(Language) -> String) -> String {
+    match language {
+        Some(language) => expr(language),
+        None => {
+            let branches: String = Language::ALL
+                .iter()
+                .map(|&language| format!(" WHEN '{}' THEN {}", language.code(), expr(language)))
+                .collect();
+            format!(r#"CASE "posts"."language"{branches} END"#)
+        }
+    }
+}
+
+impl QuerySyntax {
+    /// SQL turning the `$1` placeholder into a tsquery stemmed for `language`
+    pub fn tsquery(self, language: Language) -> String {
+        let config = search_config(language);
+        match self {
+            Self::Websearch => format!("websearch_to_tsquery({config}, $1)"),
+            Self::Plain => format!("plainto_tsquery({config}, $1)"),
+            Self::Raw => format!("to_tsquery({config}, $1)"),
+        }
+    }
+
+    /// Matches the posts whose `column` tsvector matches `query`. Without a language,
+    /// each language gets its own condition so that all of them can use the index.
+    pub fn matches(self, column: &str, query: &str, language: Option<Language>) -> SimpleExpr {
+        let sql = match language {
+            Some(language) => format!("{column} @@ {}", self.tsquery(language)),
+            None => {
+                let branches: Vec<String> = Language::ALL
+                    .iter()
+                    .map(|&language| {
+                        format!(
+                            r#"("posts"."language" = '{}' AND {column} @@ {})"#,
+                            language.code(),
+                            self.tsquery(language)
+                        )
+                    })
+                    .collect();
+                format!("({})", branches.join(" OR "))
+            }
+        };
+        Expr::cust_with_values(sql, [query])
+    }
+
+    /// Ranks posts by how well their search vector matches `query`
+    pub fn rank(self, query: &str, language: Option<Language>) -> SimpleExpr {
+        let sql = per_language(language, |language| {
+            format!("ts_rank_cd(search_vector, {})", self.tsquery(language))
+        });
+        Expr::cust_with_values(sql, [query])
+    }
+}
+
+/// Parses `query` in the database, returning why it is not a valid tsquery.
+/// Only raw queries can fail, the other syntaxes accept any input.
+pub async fn invalid_query<T: ConnectionTrait>(
+    db: &T,
+    syntax: QuerySyntax,
+    query: &str,
+    language: Language,
+) -> Result<Option<String>> {
+    if syntax != QuerySyntax::Raw {
+        return Ok(None);
+    }
+
+    let statement = Statement::from_sql_and_values(
+        db.get_database_backend(),
+        format!("SELECT {}", syntax.tsquery(language)),
+        [query.into()],
+    );
+    match db.query_one(statement).await {
+        Ok(_) => Ok(None),
+        Err(err) => match database_error(&err) {
+            Some(db_err) if db_err.code().as_deref() == Some(SYNTAX_ERROR) => {
+                Ok(Some(format!("Invalid search query: {}", db_err.message())))
+            }
+            _ => Err(InternalServerError(err)),
+        },
+    }
+}
+
+/// SQLSTATE Postgres reports for malformed tsqueries
+const SYNTAX_ERROR: &str = "42601";
+/// SQLSTATE Postgres reports for statements cancelled by `statement_timeout`
+const QUERY_CANCELED: &str = "57014";
+
+/// The error reported by Postgres itself, if that is what failed
+fn database_error(err: &DbErr) -> Option<&dyn sqlx::error::DatabaseError> {
+    match err {
+        DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(err))) => Some(err.as_ref()),
+        _ => None,
+    }
+}
+
+/// Whether a statement failed because it ran into `set_statement_timeout`
+pub fn is_timeout(err: &DbErr) -> bool {
+    database_error(err).is_some_and(|err| err.code().as_deref() == Some(QUERY_CANCELED))
+}
+
+/// Cancels statements running longer than `timeout` for the rest of the transaction
+pub async fn set_statement_timeout<T: ConnectionTrait>(db: &T, timeout: Duration) -> Result<()> {
+    let statement = Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "SELECT set_config('statement_timeout', $1, true)",
+        [timeout.as_millis().to_string().into()],
+    );
+    db.execute(statement).await.map_err(InternalServerError)?;
+    Ok(())
+}
+
+/// `LIKE` pattern matching every string that starts with `prefix`
+pub fn like_prefix(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for ch in prefix.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Lowest word similarity a fuzzy match may have when the request does not say
+pub const DEFAULT_SIMILARITY: f64 = 0.3;
+
+/// Matches the posts whose `column` contains a word similar to `query`, using the
+/// trigram index on the column and the threshold set by `set_similarity_threshold`
+pub fn resembles(column: &str, query: &str) -> SimpleExpr {
+    Expr::cust_with_values(format!("$1 <% {column}"), [query])
+}
+
+/// Ranks fuzzy matches by how close the best matching word of their title is to `query`
+pub fn similarity_rank(query: &str) -> SimpleExpr {
+    Expr::cust_with_values("word_similarity($1, title)", [query])
+}
+
+/// Sets the threshold `resembles` matches with for the rest of the transaction
+pub async fn set_similarity_threshold<T: ConnectionTrait>(db: &T, threshold: f64) -> Result<()> {
+    let statement = Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)",
+        [threshold.to_string().into()],
+    );
+    db.execute(statement).await.map_err(InternalServerError)?;
+    Ok(())
+}
+
+/// How many of the matching posts share a value
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// Counts of the posts matching a listing, by tag, author, status and month of
+/// publication, each ordered by count
+#[derive(Default, serde::Serialize, poem_openapi::Object)]
+pub struct Facets {
+    pub tags: Vec<FacetCount>,
+    pub authors: Vec<FacetCount>,
+    /// Statuses named as the `status` filter takes them, e.g. `Published`
+    pub statuses: Vec<FacetCount>,
+    /// Months posts were first published in, as `YYYY-MM`. Posts never published
+    /// are not counted.
+    pub months: Vec<FacetCount>,
+}
+
+#[derive(FromQueryResult)]
+struct FacetRow {
+    facet: String,
+    value: String,
+    count: i64,
+}
+
+/// Computes every facet of the posts selected by `query` in a single statement
+pub async fn facets<T: ConnectionTrait>(db: &T, query: Select<Posts>) -> Result<Facets> {
+    let filtered = query
+        .select_only()
+        .columns([
+            entities::posts::Column::Id,
+            entities::posts::Column::Author,
+            entities::posts::Column::PostStatus,
+            entities::posts::Column::PublishedAt,
+        ])
+        .build(db.get_database_backend());
+    let sql = format!(
+        r#"
+        WITH filtered AS ({})
+        SELECT 'tag' AS facet, tags.name AS value, count(*) AS count
+        FROM filtered
+        JOIN post_tags ON post_tags.post_id = filtered.id
+        JOIN tags ON tags.id = post_tags.tag_id
+        GROUP BY tags.name
+        UNION ALL
+        SELECT 'author', author, count(*) FROM filtered GROUP BY author
+        UNION ALL
+        SELECT 'status', post_status::text, count(*) FROM filtered GROUP BY post_status
+        UNION ALL
+        SELECT 'month', to_char(published_at, 'YYYY-MM'), count(*)
+        FROM filtered
+        WHERE published_at IS NOT NULL
+        GROUP BY to_char(published_at, 'YYYY-MM')
+        ORDER BY facet, count DESC, value
+        "#,
+        filtered.sql
+    );
+    let statement = Statement::from_sql_and_values(
+        db.get_database_backend(),
+        sql,
+        filtered.values.map(|values| values.0).unwrap_or_default(),
+    );
+    let rows = FacetRow::find_by_statement(statement)
+        .all(db)
+        .await
+        .map_err(InternalServerError)?;
+
+    let mut facets = Facets::default();
+    for row in rows {
+        let (counts, value) = match row.facet.as_str() {
+            "tag" => (&mut facets.tags, row.value),
+            "author" => (&mut facets.authors, row.value),
+            "status" => (&mut facets.statuses, status_name(row.value)),
+            _ => (&mut facets.months, row.value),
+        };
+        counts.push(FacetCount {
+            value,
+            count: row.count,
+        });
+    }
+    Ok(facets)
+}
+
+/// Name the `status` filter takes for a status as stored in the database, so that
+/// facet values can be passed back as filters
+fn status_name(value: String) -> String {
+    PostsStatusEnum::try_from_value(&value)
+        .ok()
+        .and_then(|status| status.to_json())
+        .and_then(|name| name.as_str().map(str::to_string))
+        .unwrap_or(value)
+}
+
+/// Why the highlight parameters of a search were rejected
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidHighlight {
+    #[error("Highlight markers must be between 1 and {MAX_MARKER_LENGTH} characters")]
+    MarkerLength,
+    #[error("Highlight markers cannot contain '\"' or '\\'")]
+    MarkerCharacters,
+    #[error("At most {MAX_FRAGMENTS} fragments can be highlighted")]
+    TooManyFragments,
+}
+
+/// Markers put around matched words and how many body fragments `ts_headline` returns
+pub struct HighlightOptions {
+    start: String,
+    stop: String,
+    max_fragments: u32,
+}
+
+impl HighlightOptions {
+    pub fn new(
+        start: Option<String>,
+        stop: Option<String>,
+        max_fragments: Option<u32>,
+    ) -> Result<Self, InvalidHighlight> {
+        let start = start.unwrap_or_else(|| DEFAULT_START.to_string());
+        let stop = stop.unwrap_or_else(|| DEFAULT_STOP.to_string());
+        for marker in [&start, &stop] {
+            if marker.is_empty() || marker.chars().count() > MAX_MARKER_LENGTH {
+                return Err(InvalidHighlight::MarkerLength);
+            }
+            // They end up quoted inside the ts_headline options string
+            if marker.contains(['"', '\\']) {
+                return Err(InvalidHighlight::MarkerCharacters);
+            }
+        }
+        let max_fragments = max_fragments.unwrap_or(DEFAULT_MAX_FRAGMENTS);
+        if max_fragments > MAX_FRAGMENTS {
+            return Err(InvalidHighlight::TooManyFragments);
+        }
+        Ok(Self {
+            start,
+            stop,
+            max_fragments,
+        })
+    }
+
+    /// `ts_headline` options string, zero fragments highlights the whole text
+    fn headline_options(&self, max_fragments: u32) -> String {
+        format!(
+            r#"StartSel="{}", StopSel="{}", MaxFragments={max_fragments}, FragmentDelimiter=" … ""#,
+            self.start, self.stop
+        )
+    }
+}
+
+/// Title and body excerpts of a search result with the matched words marked
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct PostHighlight {
+    pub slug: String,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(FromQueryResult)]
+struct HighlightRow {
+    id: Uuid,
+    slug: String,
+    title: String,
+    body: String,
+}
+
+/// Highlights `query` in the posts, returned in the order of `post_ids`
+pub async fn highlights<T: ConnectionTrait>(
+    db: &T,
+    syntax: QuerySyntax,
+    query: &str,
+    language: Option<Language>,
+    post_ids: &[Uuid],
+    options: &HighlightOptions,
+) -> Result<Vec<PostHighlight>> {
+    if post_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let headline = |column: &str| {
+        per_language(language, |language| {
+            format!(
+                "ts_headline({}, {column}, {}, $2)",
+                search_config(language),
+                syntax.tsquery(language)
+            )
+        })
+    };
+    let rows: Vec<HighlightRow> = Posts::find()
+        .select_only()
+        .column(entities::posts::Column::Id)
+        .column(entities::posts::Column::Slug)
+        .column_as(
+            Expr::cust_with_values(
+                headline("title"),
+                [query.to_string(), options.headline_options(0)],
+            ),
+            "title",
+        )
+        .column_as(
+            Expr::cust_with_values(
+                headline("body"),
+                [
+                    query.to_string(),
+                    options.headline_options(options.max_fragments),
+                ],
+            ),
+            "body",
+        )
+        .filter(entities::posts::Column::Id.is_in(post_ids.to_vec()))
+        .into_model()
+        .all(db)
+        .await
+        .map_err(InternalServerError)?;
+
+    let mut rows: HashMap<Uuid, HighlightRow> = rows.into_iter().map(|row| (row.id, row)).collect();
+    Ok(post_ids
+        .iter()
+        .filter_map(|id| rows.remove(id))
+        .map(|row| PostHighlight {
+            slug: row.slug,
+            title: row.title,
+            body: row.body,
+        })
+        .collect())
+}
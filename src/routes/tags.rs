@@ -0,0 +1,798 @@
+use std::collections::HashMap;
+
+use crate::auth::{BearerAuthorization, OptionalBearerAuthorization};
+use crate::jobs::orphan_tags::remove_orphan_tags;
+use crate::routes::ApiTags;
+use crate::routes::posts::readable_by;
+
+use entities::post_tags::Entity as PostTags;
+use entities::posts::Entity as Posts;
+use entities::tag_aliases::Entity as TagAliases;
+use entities::tags::Entity as Tags;
+use migration::{Expr, OnConflict, Query as InsertQuery, SimpleExpr};
+use poem::Error;
+use poem::{Result, error::InternalServerError, web::Data};
+use poem_openapi::param::Query;
+use poem_openapi::payload::PlainText;
+use poem_openapi::{ApiResponse, OpenApi, param::Path, payload::Json};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    ModelTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+pub struct TagsApi;
+
+/// Endpoints about every tag at once. They live outside `/tags` so that no tag name
+/// can shadow them, tags are created from whatever names posts are tagged with.
+pub struct TagCollectionApi;
+
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct TagWithCount {
+    pub id: Uuid,
+    pub name: String,
+    /// What the tag is matched by: its name lowercased, with runs of whitespace collapsed
+    pub normalized_name: String,
+    pub parent_id: Option<Uuid>,
+    /// Number of posts carrying the tag that the caller can read
+    pub post_count: i64,
+}
+
+/// A tag with its sub tags, as returned by the category tree
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct TagNode {
+    pub id: Uuid,
+    pub name: String,
+    pub normalized_name: String,
+    pub children: Vec<TagNode>,
+}
+
+#[derive(serde::Deserialize, poem_openapi::Object)]
+pub struct SetParentRequest {
+    /// Name of the new parent tag, `null` makes the tag a root category
+    pub parent: Option<String>,
+}
+
+#[derive(serde::Deserialize, poem_openapi::Object)]
+pub struct RenameTagRequest {
+    pub name: String,
+}
+
+#[derive(serde::Deserialize, poem_openapi::Object)]
+pub struct MergeTagsRequest {
+    /// Name of the tag that survives the merge
+    pub into: String,
+}
+
+#[derive(serde::Deserialize, poem_openapi::Object)]
+pub struct AddAliasRequest {
+    pub alias: String,
+}
+
+#[derive(ApiResponse)]
+enum ListTagsResponse {
+    #[oai(status = 200)]
+    Ok(Json<Vec<TagWithCount>>),
+}
+
+#[derive(ApiResponse)]
+enum GetTagResponse {
+    #[oai(status = 200)]
+    Ok(Json<TagWithCount>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+#[derive(ApiResponse)]
+enum RenameTagResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound,
+    #[oai(status = 409)]
+    Conflict(PlainText<String>),
+    #[oai(status = 400)]
+    BadRequest(PlainText<String>),
+}
+
+#[derive(ApiResponse)]
+enum DeleteTagResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+#[derive(ApiResponse)]
+enum ListAliasesResponse {
+    #[oai(status = 200)]
+    Ok(Json<Vec<String>>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+#[derive(ApiResponse)]
+enum AddAliasResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound,
+    #[oai(status = 409)]
+    Conflict(PlainText<String>),
+    #[oai(status = 400)]
+    BadRequest(PlainText<String>),
+}
+
+#[derive(ApiResponse)]
+enum DeleteAliasResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+/// Outcome of an orphaned tag cleanup
+#[derive(serde::Serialize, poem_openapi::Object)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    /// Names of the tags deleted, or that would be deleted on a dry run
+    pub tags: Vec<String>,
+}
+
+#[derive(ApiResponse)]
+enum CleanupTagsResponse {
+    #[oai(status = 200)]
+    Ok(Json<CleanupReport>),
+}
+
+#[derive(ApiResponse)]
+enum TagTreeResponse {
+    #[oai(status = 200)]
+    Ok(Json<Vec<TagNode>>),
+}
+
+#[derive(ApiResponse)]
+enum SetParentResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound(PlainText<String>),
+    #[oai(status = 400)]
+    BadRequest(PlainText<String>),
+}
+
+#[derive(ApiResponse)]
+enum MergeTagsResponse {
+    #[oai(status = 200)]
+    Ok(PlainText<String>),
+    #[oai(status = 404)]
+    NotFound,
+    #[oai(status = 400)]
+    BadRequest(PlainText<String>),
+}
+
+/// Number of readable posts per tag id, tags without any are left out
+async fn post_counts<T: ConnectionTrait>(
+    db: &T,
+    auth: &OptionalBearerAuthorization,
+    tag_id: Option<Uuid>,
+) -> Result<HashMap<Uuid, i64>> {
+    let mut query = PostTags::find()
+        .select_only()
+        .column(entities::post_tags::Column::TagId)
+        .column_as(entities::post_tags::Column::PostId.count(), "post_count")
+        .inner_join(Posts)
+        .filter(readable_by(auth))
+        .group_by(entities::post_tags::Column::TagId);
+    if let Some(tag_id) = tag_id {
+        query = query.filter(entities::post_tags::Column::TagId.eq(tag_id));
+    }
+    let counts: Vec<(Uuid, i64)> = query
+        .into_tuple()
+        .all(db)
+        .await
+        .map_err(InternalServerError)?;
+    Ok(counts.into_iter().collect())
+}
+
+/// Display form of a client supplied tag name, with runs of whitespace collapsed
+pub(crate) fn display_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// What tags and aliases are matched by: the display name lowercased. It keeps
+/// punctuation, so "C", "C++" and "C#" stay three tags.
+pub(crate) fn normalized_name(name: &str) -> String {
+    display_name(name).to_lowercase()
+}
+
+/// Finds the tag a client supplied name refers to, by its normalized name or one of
+/// its aliases.
+/// Names differing only in case or spacing refer to the same tag.
+pub(crate) async fn resolve_tag<T: ConnectionTrait>(
+    db: &T,
+    name: &str,
+) -> Result<Option<entities::tags::Model>> {
+    let normalized = normalized_name(name);
+    if normalized.is_empty() {
+        return Ok(None);
+    }
+
+    let tag = Tags::find()
+        .filter(entities::tags::Column::NormalizedName.eq(&normalized))
+        .one(db)
+        .await
+        .map_err(InternalServerError)?;
+    if tag.is_some() {
+        return Ok(tag);
+    }
+
+    let alias = TagAliases::find_by_id(normalized)
+        .find_also_related(Tags)
+        .one(db)
+        .await
+        .map_err(InternalServerError)?;
+    Ok(alias.and_then(|(_, tag)| tag))
+}
+
+/// Ids of the tags the names refer to, `None` for names matching no tag
+pub(crate) async fn resolve_tag_ids<T: ConnectionTrait>(
+    db: &T,
+    names: &[String],
+) -> Result<Vec<Option<Uuid>>> {
+    let mut tag_ids = Vec::with_capacity(names.len());
+    for name in names {
+        tag_ids.push(resolve_tag(db, name).await?.map(|tag| tag.id));
+    }
+    Ok(tag_ids)
+}
+
+/// Whether `tag` is `ancestor_id` or sits anywhere below it, found by walking up its parents
+async fn descends_from<T: ConnectionTrait>(
+    db: &T,
+    tag: &entities::tags::Model,
+    ancestor_id: Uuid,
+) -> Result<bool> {
+    let mut current = Some(tag.clone());
+    while let Some(tag) = current {
+        if tag.id == ancestor_id {
+            return Ok(true);
+        }
+        current = match tag.parent_id {
+            Some(parent_id) => Tags::find_by_id(parent_id)
+                .one(db)
+                .await
+                .map_err(InternalServerError)?,
+            None => None,
+        };
+    }
+    Ok(false)
+}
+
+async fn add_alias<T: ConnectionTrait>(
+    db: &T,
+    normalized_name: String,
+    tag_id: Uuid,
+) -> Result<(), DbErr> {
+    let alias = entities::tag_aliases::ActiveModel {
+        normalized_name: Set(normalized_name),
+        tag_id: Set(tag_id),
+    };
+    alias.insert(db).await?;
+    Ok(())
+}
+
+/// Matches the posts carrying the tag or any tag below it in the category tree
+pub(crate) fn in_category(tag_id: Uuid) -> SimpleExpr {
+    Expr::cust_with_values(
+        r#""posts"."id" IN (
+            WITH RECURSIVE category AS (
+                SELECT id FROM tags WHERE id = $1
+                UNION
+                SELECT tags.id FROM tags JOIN category ON tags.parent_id = category.id
+            )
+            SELECT post_tags.post_id FROM post_tags JOIN category ON category.id = post_tags.tag_id
+        )"#,
+        [tag_id],
+    )
+}
+
+/// Builds the subtrees below `parent`, ordered by name
+fn tag_nodes(
+    children: &mut HashMap<Option<Uuid>, Vec<entities::tags::Model>>,
+    parent: Option<Uuid>,
+) -> Vec<TagNode> {
+    let Some(tags) = children.remove(&parent) else {
+        return Vec::new();
+    };
+    tags.into_iter()
+        .map(|tag| TagNode {
+            children: tag_nodes(children, Some(tag.id)),
+            id: tag.id,
+            name: tag.name,
+            normalized_name: tag.normalized_name,
+        })
+        .collect()
+}
+
+/// Bumps `last_edit` of every post carrying the tag, so cached copies showing
+/// the old tag are revalidated
+async fn touch_tagged_posts<T: ConnectionTrait>(db: &T, tag_id: Uuid) -> Result<(), DbErr> {
+    let tagged = PostTags::find()
+        .select_only()
+        .column(entities::post_tags::Column::PostId)
+        .filter(entities::post_tags::Column::TagId.eq(tag_id))
+        .into_query();
+    Posts::update_many()
+        .col_expr(
+            entities::posts::Column::LastEdit,
+            Expr::value(chrono::Utc::now().naive_utc()),
+        )
+        .filter(entities::posts::Column::Id.in_subquery(tagged))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[OpenApi(tag = "ApiTags::Tags")]
+impl TagCollectionApi {
+    /// Every tag arranged by parent, root categories first
+    #[oai(method = "get", path = "/tag_tree")]
+    async fn tag_tree(&self, db: Data<&DatabaseConnection>) -> Result<TagTreeResponse> {
+        let tags = Tags::find()
+            .order_by_asc(entities::tags::Column::Name)
+            .all(*db)
+            .await
+            .map_err(InternalServerError)?;
+
+        let mut children: HashMap<Option<Uuid>, Vec<entities::tags::Model>> = HashMap::new();
+        for tag in tags {
+            children.entry(tag.parent_id).or_default().push(tag);
+        }
+
+        Ok(TagTreeResponse::Ok(Json(tag_nodes(&mut children, None))))
+    }
+
+    /// Deletes the tags no post carries, the same cleanup the background job runs
+    #[oai(method = "post", path = "/tag_maintenance/cleanup")]
+    async fn cleanup_tags(
+        &self,
+        dry_run: Query<Option<bool>>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<CleanupTagsResponse> {
+        if !claims.has_permission("delete", "tag") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let dry_run = dry_run.0.unwrap_or(false);
+        let tags = remove_orphan_tags(*db, dry_run)
+            .await
+            .map_err(InternalServerError)?;
+
+        Ok(CleanupTagsResponse::Ok(Json(CleanupReport {
+            dry_run,
+            tags: tags.into_iter().map(|tag| tag.name).collect(),
+        })))
+    }
+}
+
+#[OpenApi(prefix_path = "/tags", tag = "ApiTags::Tags")]
+impl TagsApi {
+    #[oai(method = "get", path = "/")]
+    async fn list_tags(
+        &self,
+        auth: OptionalBearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<ListTagsResponse> {
+        let tags = Tags::find()
+            .order_by_asc(entities::tags::Column::Name)
+            .all(*db)
+            .await
+            .map_err(InternalServerError)?;
+        let counts = post_counts(*db, &auth, None).await?;
+
+        let tags = tags
+            .into_iter()
+            .map(|tag| TagWithCount {
+                post_count: counts.get(&tag.id).copied().unwrap_or(0),
+                id: tag.id,
+                name: tag.name,
+                normalized_name: tag.normalized_name,
+                parent_id: tag.parent_id,
+            })
+            .collect();
+
+        Ok(ListTagsResponse::Ok(Json(tags)))
+    }
+
+    #[oai(method = "get", path = "/:tag_name")]
+    async fn get_tag(
+        &self,
+        tag_name: Path<String>,
+        auth: OptionalBearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<GetTagResponse> {
+        let Some(tag) = resolve_tag(*db, &tag_name.0).await? else {
+            return Ok(GetTagResponse::NotFound);
+        };
+        let counts = post_counts(*db, &auth, Some(tag.id)).await?;
+
+        Ok(GetTagResponse::Ok(Json(TagWithCount {
+            post_count: counts.get(&tag.id).copied().unwrap_or(0),
+            id: tag.id,
+            name: tag.name,
+            normalized_name: tag.normalized_name,
+            parent_id: tag.parent_id,
+        })))
+    }
+
+    #[oai(method = "patch", path = "/:tag_name")]
+    async fn rename_tag(
+        &self,
+        tag_name: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+        request: Json<RenameTagRequest>,
+    ) -> Result<RenameTagResponse> {
+        if !claims.has_permission("update", "tag") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let name = display_name(&request.name);
+        let normalized = normalized_name(&name);
+        if normalized.is_empty() {
+            return Ok(RenameTagResponse::BadRequest(PlainText(
+                "Tag name cannot be empty".to_string(),
+            )));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let Some(tag) = resolve_tag(&tnx, &tag_name.0).await? else {
+            return Ok(RenameTagResponse::NotFound);
+        };
+        if tag.name == name {
+            return Ok(RenameTagResponse::Ok(PlainText(tag.name)));
+        }
+        match resolve_tag(&tnx, &name).await? {
+            Some(other) if other.id != tag.id => {
+                return Ok(RenameTagResponse::Conflict(PlainText(format!(
+                    "Tag {} already exists, merge the tags instead",
+                    other.name
+                ))));
+            }
+            _ => {}
+        }
+
+        // The old name keeps resolving to the tag, the new one stops being an alias
+        if normalized != tag.normalized_name {
+            TagAliases::delete_by_id(normalized.clone())
+                .exec(&tnx)
+                .await
+                .map_err(InternalServerError)?;
+            add_alias(&tnx, tag.normalized_name.clone(), tag.id)
+                .await
+                .map_err(InternalServerError)?;
+        }
+
+        touch_tagged_posts(&tnx, tag.id)
+            .await
+            .map_err(InternalServerError)?;
+        let mut tag: entities::tags::ActiveModel = tag.into();
+        tag.name = Set(name);
+        tag.normalized_name = Set(normalized);
+        let tag = tag.update(&tnx).await.map_err(InternalServerError)?;
+
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(RenameTagResponse::Ok(PlainText(tag.name)))
+    }
+
+    /// Deletes a tag, detaching it from every post that carries it
+    #[oai(method = "delete", path = "/:tag_name")]
+    async fn delete_tag(
+        &self,
+        tag_name: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<DeleteTagResponse> {
+        if !claims.has_permission("delete", "tag") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let Some(tag) = resolve_tag(&tnx, &tag_name.0).await? else {
+            return Ok(DeleteTagResponse::NotFound);
+        };
+
+        touch_tagged_posts(&tnx, tag.id)
+            .await
+            .map_err(InternalServerError)?;
+        PostTags::delete_many()
+            .filter(entities::post_tags::Column::TagId.eq(tag.id))
+            .exec(&tnx)
+            .await
+            .map_err(InternalServerError)?;
+        // Its aliases go with it
+        tag.delete(&tnx).await.map_err(InternalServerError)?;
+
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(DeleteTagResponse::Ok(PlainText(format!(
+            "Tag {} deleted",
+            tag_name.0
+        ))))
+    }
+
+    /// Moves every post of a tag over to another tag and deletes the now unused one
+    #[oai(method = "post", path = "/:tag_name/merge")]
+    async fn merge_tags(
+        &self,
+        tag_name: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+        request: Json<MergeTagsRequest>,
+    ) -> Result<MergeTagsResponse> {
+        if !claims.has_permission("update", "tag") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let Some(source) = resolve_tag(&tnx, &tag_name.0).await? else {
+            return Ok(MergeTagsResponse::NotFound);
+        };
+        let Some(target) = resolve_tag(&tnx, &request.into).await? else {
+            return Ok(MergeTagsResponse::NotFound);
+        };
+        if source.id == target.id {
+            return Ok(MergeTagsResponse::BadRequest(PlainText(
+                "Cannot merge a tag into itself".to_string(),
+            )));
+        }
+        // The target would end up its own ancestor once the source's children move to it
+        if descends_from(&tnx, &target, source.id).await? {
+            return Ok(MergeTagsResponse::BadRequest(PlainText(
+                "Cannot merge a tag into one of its sub tags".to_string(),
+            )));
+        }
+
+        touch_tagged_posts(&tnx, source.id)
+            .await
+            .map_err(InternalServerError)?;
+
+        // Posts already carrying both tags keep their existing row for the target
+        let source_posts = PostTags::find()
+            .select_only()
+            .column(entities::post_tags::Column::PostId)
+            .column_as(Expr::value(target.id), "tag_id")
+            .filter(entities::post_tags::Column::TagId.eq(source.id))
+            .into_query();
+        let insert = InsertQuery::insert()
+            .into_table(PostTags)
+            .columns([
+                entities::post_tags::Column::PostId,
+                entities::post_tags::Column::TagId,
+            ])
+            .select_from(source_posts)
+            .map_err(InternalServerError)?
+            .on_conflict(
+                OnConflict::columns([
+                    entities::post_tags::Column::PostId,
+                    entities::post_tags::Column::TagId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .to_owned();
+        tnx.execute(tnx.get_database_backend().build(&insert))
+            .await
+            .map_err(InternalServerError)?;
+
+        Tags::update_many()
+            .col_expr(entities::tags::Column::ParentId, Expr::value(target.id))
+            .filter(entities::tags::Column::ParentId.eq(source.id))
+            .filter(entities::tags::Column::Id.ne(target.id))
+            .exec(&tnx)
+            .await
+            .map_err(InternalServerError)?;
+
+        // The merged tag and its aliases become aliases of the surviving tag
+        TagAliases::update_many()
+            .col_expr(entities::tag_aliases::Column::TagId, Expr::value(target.id))
+            .filter(entities::tag_aliases::Column::TagId.eq(source.id))
+            .exec(&tnx)
+            .await
+            .map_err(InternalServerError)?;
+        PostTags::delete_many()
+            .filter(entities::post_tags::Column::TagId.eq(source.id))
+            .exec(&tnx)
+            .await
+            .map_err(InternalServerError)?;
+        let source_name = source.normalized_name.clone();
+        source.delete(&tnx).await.map_err(InternalServerError)?;
+        add_alias(&tnx, source_name, target.id)
+            .await
+            .map_err(InternalServerError)?;
+
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(MergeTagsResponse::Ok(PlainText(target.name)))
+    }
+
+    /// Moves a tag, with everything below it, under another tag
+    #[oai(method = "put", path = "/:tag_name/parent")]
+    async fn set_parent(
+        &self,
+        tag_name: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+        request: Json<SetParentRequest>,
+    ) -> Result<SetParentResponse> {
+        if !claims.has_permission("update", "tag") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let Some(tag) = resolve_tag(&tnx, &tag_name.0).await? else {
+            return Ok(SetParentResponse::NotFound(PlainText(format!(
+                "Tag {} not found",
+                tag_name.0
+            ))));
+        };
+        let parent = match &request.parent {
+            Some(parent_name) => match resolve_tag(&tnx, parent_name).await? {
+                Some(parent) => Some(parent),
+                None => {
+                    return Ok(SetParentResponse::NotFound(PlainText(format!(
+                        "Tag {parent_name} not found"
+                    ))));
+                }
+            },
+            None => None,
+        };
+
+        if let Some(parent) = &parent
+            && descends_from(&tnx, parent, tag.id).await?
+        {
+            return Ok(SetParentResponse::BadRequest(PlainText(
+                "A tag cannot be placed below itself".to_string(),
+            )));
+        }
+
+        let mut tag: entities::tags::ActiveModel = tag.into();
+        tag.parent_id = Set(parent.map(|parent| parent.id));
+        let tag = tag.update(&tnx).await.map_err(InternalServerError)?;
+
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(SetParentResponse::Ok(PlainText(tag.name)))
+    }
+
+    #[oai(method = "get", path = "/:tag_name/aliases")]
+    async fn list_aliases(
+        &self,
+        tag_name: Path<String>,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<ListAliasesResponse> {
+        let Some(tag) = resolve_tag(*db, &tag_name.0).await? else {
+            return Ok(ListAliasesResponse::NotFound);
+        };
+
+        let aliases = tag
+            .find_related(TagAliases)
+            .order_by_asc(entities::tag_aliases::Column::NormalizedName)
+            .all(*db)
+            .await
+            .map_err(InternalServerError)?;
+
+        Ok(ListAliasesResponse::Ok(Json(
+            aliases
+                .into_iter()
+                .map(|alias| alias.normalized_name)
+                .collect(),
+        )))
+    }
+
+    /// Makes another name resolve to the tag everywhere tags are given by name
+    #[oai(method = "post", path = "/:tag_name/aliases")]
+    async fn add_alias(
+        &self,
+        tag_name: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+        request: Json<AddAliasRequest>,
+    ) -> Result<AddAliasResponse> {
+        if !claims.has_permission("update", "tag") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let alias = normalized_name(&request.alias);
+        if alias.is_empty() {
+            return Ok(AddAliasResponse::BadRequest(PlainText(
+                "Alias cannot be empty".to_string(),
+            )));
+        }
+
+        let tnx = db.begin().await.map_err(InternalServerError)?;
+
+        let Some(tag) = resolve_tag(&tnx, &tag_name.0).await? else {
+            return Ok(AddAliasResponse::NotFound);
+        };
+        match resolve_tag(&tnx, &alias).await? {
+            Some(other) if other.id == tag.id => {
+                return Ok(AddAliasResponse::Ok(PlainText(alias)));
+            }
+            Some(other) => {
+                return Ok(AddAliasResponse::Conflict(PlainText(format!(
+                    "{alias} already refers to tag {}",
+                    other.name
+                ))));
+            }
+            None => {}
+        }
+
+        add_alias(&tnx, alias.clone(), tag.id)
+            .await
+            .map_err(InternalServerError)?;
+        tnx.commit().await.map_err(InternalServerError)?;
+
+        Ok(AddAliasResponse::Ok(PlainText(alias)))
+    }
+
+    #[oai(method = "delete", path = "/:tag_name/aliases/:alias")]
+    async fn delete_alias(
+        &self,
+        tag_name: Path<String>,
+        alias: Path<String>,
+        claims: BearerAuthorization,
+        db: Data<&DatabaseConnection>,
+    ) -> Result<DeleteAliasResponse> {
+        if !claims.has_permission("update", "tag") {
+            return Err(Error::from_string(
+                "Not enough permissions",
+                poem::http::StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let Some(tag) = resolve_tag(*db, &tag_name.0).await? else {
+            return Ok(DeleteAliasResponse::NotFound);
+        };
+
+        let deleted = TagAliases::delete_many()
+            .filter(entities::tag_aliases::Column::NormalizedName.eq(normalized_name(&alias.0)))
+            .filter(entities::tag_aliases::Column::TagId.eq(tag.id))
+            .exec(*db)
+            .await
+            .map_err(InternalServerError)?;
+        if deleted.rows_affected == 0 {
+            return Ok(DeleteAliasResponse::NotFound);
+        }
+
+        Ok(DeleteAliasResponse::Ok(PlainText(format!(
+            "Alias {} deleted",
+            alias.0
+        ))))
+    }
+}
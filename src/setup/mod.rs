@@ -1,4 +1,4 @@
-use crate::config;
+use crate::{config, jobs};
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
 
 pub async fn db_init(database_url: &str) -> anyhow::Result<DatabaseConnection> {
@@ -14,6 +14,7 @@ pub struct SetupResult {
 
 pub async fn setup_all() -> anyhow::Result<SetupResult> {
     let db = db_init(&config::CONFIG.database_url).await?;
+    jobs::spawn_all(&db);
    // let object_storage = get_object_storage()?;
     Ok(SetupResult { db, })
 }
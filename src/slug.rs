@@ -0,0 +1,169 @@
+use deunicode::deunicode;
+
+/// Placed between the words of a slug
+pub const SEPARATOR: char = '_';
+
+/// Longest slug we hand out, suffixes included
+pub const MAX_LENGTH: usize = 96;
+
+/// Used when a title has nothing that survives slugification, e.g. only punctuation
+const FALLBACK: &str = "post";
+
+/// Static paths under `/posts` that a post slug would be shadowed by
+const RESERVED: &[&str] = &["bulk_get", "suggest", "trash"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidSlug {
+    #[error("Slug cannot be empty")]
+    Empty,
+    #[error("Slug cannot be longer than {MAX_LENGTH} characters")]
+    TooLong,
+    #[error("Slug may only contain lowercase ascii letters and digits separated by single '_'")]
+    NotNormalized,
+    #[error("Slug {0} is reserved")]
+    Reserved(String),
+}
+
+/// Transliterates `text` to ASCII, lowercases it, turns every run of other characters
+/// into a single separator and caps the result at `MAX_LENGTH`
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for ch in deunicode(text).chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with(SEPARATOR) {
+            slug.push(SEPARATOR);
+        }
+    }
+    truncate(&slug, MAX_LENGTH).to_string()
+}
+
+/// Slug generated for a post title
+pub fn from_title(title: &str) -> String {
+    let slug = slugify(title);
+    if slug.is_empty() {
+        FALLBACK.to_string()
+    } else {
+        slug
+    }
+}
+
+/// Appends a numeric suffix to `base`, shortening it if needed to stay under `MAX_LENGTH`
+pub fn with_suffix(base: &str, suffix: u32) -> String {
+    let suffix = format!("{SEPARATOR}{suffix}");
+    format!("{}{suffix}", truncate(base, MAX_LENGTH - suffix.len()))
+}
+
+/// Checks a client supplied slug is one `slugify` could have produced
+pub fn validate(slug: &str) -> Result<(), InvalidSlug> {
+    if slug.is_empty() {
+        return Err(InvalidSlug::Empty);
+    }
+    if slug.len() > MAX_LENGTH {
+        return Err(InvalidSlug::TooLong);
+    }
+    if slugify(slug) != slug {
+        return Err(InvalidSlug::NotNormalized);
+    }
+    if is_reserved(slug) {
+        return Err(InvalidSlug::Reserved(slug.to_string()));
+    }
+    Ok(())
+}
+
+/// Whether `slug` collides with a route and can't be given to a post
+pub fn is_reserved(slug: &str) -> bool {
+    RESERVED.contains(&slug)
+}
+
+/// Slugs are ASCII, so cutting at any byte is safe
+fn truncate(slug: &str, max_length: usize) -> &str {
+    let slug = &slug[..slug.len().min(max_length)];
+    slug.trim_end_matches(SEPARATOR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_transliterates_and_collapses_separators() {
+        assert_eq!(slugify("Crème Brûlée -- 101!"), "creme_brulee_101");
+        assert_eq!(slugify("  Hello,   World  "), "hello_world");
+        assert_eq!(slugify("Привет мир"), "privet_mir");
+    }
+
+    #[test]
+    fn from_title_falls_back_when_nothing_survives() {
+        assert_eq!(from_title("?!…"), FALLBACK);
+        assert_eq!(from_title("Rust 2024"), "rust_2024");
+    }
+
+    #[test]
+    fn slugify_caps_length_without_trailing_separator() {
+        let title = format!("{} tail", "a".repeat(MAX_LENGTH - 1));
+        let slug = slugify(&title);
+        assert_eq!(slug.len(), MAX_LENGTH - 1);
+        assert!(!slug.ends_with(SEPARATOR));
+    }
+
+    #[test]
+    fn with_suffix_stays_under_max_length() {
+        assert_eq!(with_suffix("post", 2), "post_2");
+
+        let base = "a".repeat(MAX_LENGTH);
+        let slug = with_suffix(&base, 12);
+        assert_eq!(slug.len(), MAX_LENGTH);
+        assert!(slug.ends_with("_12"));
+    }
+
+    #[test]
+    fn with_suffix_does_not_double_separators() {
+        // Cutting room for the suffix leaves the base ending in a separator
+        let base = format!("{}_bb", "a".repeat(MAX_LENGTH - 3));
+        assert_eq!(
+            with_suffix(&base, 3),
+            format!("{}_3", "a".repeat(MAX_LENGTH - 3))
+        );
+    }
+
+    #[test]
+    fn validate_accepts_only_normalized_slugs() {
+        assert!(validate("hello_world").is_ok());
+        assert!(matches!(validate(""), Err(InvalidSlug::Empty)));
+        assert!(matches!(
+            validate(&"a".repeat(MAX_LENGTH + 1)),
+            Err(InvalidSlug::TooLong)
+        ));
+        for slug in ["Hello", "hello__world", "_hello", "hello_", "héllo", "a-b"] {
+            assert!(
+                matches!(validate(slug), Err(InvalidSlug::NotNormalized)),
+                "{slug} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_rejects_reserved_slugs() {
+        for slug in RESERVED {
+            assert!(is_reserved(slug));
+            assert!(matches!(validate(slug), Err(InvalidSlug::Reserved(_))));
+        }
+        assert!(!is_reserved("suggestions"));
+    }
+
+    #[test]
+    fn every_static_post_path_is_reserved() {
+        let spec = poem_openapi::OpenApiService::new(crate::routes::api(), "", "").spec();
+        let spec: serde_json::Value = serde_json::from_str(&spec).unwrap();
+        for path in spec["paths"].as_object().unwrap().keys() {
+            let Some(segment) = path.strip_prefix("/posts/") else {
+                continue;
+            };
+            let segment = segment.split('/').next().unwrap();
+            if !segment.starts_with('{') {
+                assert!(is_reserved(segment), "{path} is not reserved");
+            }
+        }
+    }
+}